@@ -1,34 +1,255 @@
-use std::f32::consts::FRAC_PI_2;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
 
 use bevy::{
-    input::mouse::MouseMotion,
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    pbr::{VolumetricFogSettings, VolumetricLight},
     prelude::*,
+    render::{camera::ScalingMode, primitives::Aabb},
     window::{CursorGrabMode, PrimaryWindow},
 };
 
-use crate::EditorAction;
+use serde::{Deserialize, Serialize};
+
+use crate::{EditorAction, EditorEntity, SelectedEntities};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum CameraMode {
+    /// Right mouse to look, WASDQE to move
+    #[default]
+    Fly,
+    /// Flying, plus middle mouse to orbit, Shift + middle mouse to pan and scroll to zoom
+    Orbit,
+}
 
 #[derive(Component)]
 pub struct Flycam {
+    pub mode: CameraMode,
     pub speed: f32,
     pub sensitivity: f32,
+
+    /// The point orbited around in [`CameraMode::Orbit`]
+    pub focus: Vec3,
+    /// Orbit around the center of the selected entities instead of `focus`
+    pub orbit_selection: bool,
+
+    /// The axis aligned view the camera is in, cleared when the camera is rotated
+    pub view: Option<ViewDirection>,
 }
 
 impl Default for Flycam {
     fn default() -> Self {
         Self {
+            mode: CameraMode::default(),
             speed: 4.0,
             sensitivity: 0.005,
+            focus: Vec3::ZERO,
+            orbit_selection: true,
+            view: None,
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum ViewDirection {
+    Top,
+    Bottom,
+    Front,
+    Back,
+    Right,
+    Left,
+}
+
+impl ViewDirection {
+    pub const ALL: [ViewDirection; 6] = [
+        ViewDirection::Top,
+        ViewDirection::Bottom,
+        ViewDirection::Front,
+        ViewDirection::Back,
+        ViewDirection::Right,
+        ViewDirection::Left,
+    ];
+
+    /// The direction from the focus to the camera
+    pub fn offset(self) -> Vec3 {
+        match self {
+            ViewDirection::Top => Vec3::Y,
+            ViewDirection::Bottom => Vec3::NEG_Y,
+            ViewDirection::Front => Vec3::Z,
+            ViewDirection::Back => Vec3::NEG_Z,
+            ViewDirection::Right => Vec3::X,
+            ViewDirection::Left => Vec3::NEG_X,
+        }
+    }
+
+    /// The axis label shown in the orientation widget
+    pub fn label(self) -> &'static str {
+        match self {
+            ViewDirection::Top => "Y",
+            ViewDirection::Bottom => "-Y",
+            ViewDirection::Front => "Z",
+            ViewDirection::Back => "-Z",
+            ViewDirection::Right => "X",
+            ViewDirection::Left => "-X",
+        }
+    }
+
+    /// The up direction of the camera in this view
+    pub fn up(self) -> Vec3 {
+        match self {
+            ViewDirection::Top => Vec3::NEG_Z,
+            ViewDirection::Bottom => Vec3::Z,
+            _ => Vec3::Y,
+        }
+    }
+}
+
+/// The editor camera of the viewport the cursor was last over.
+/// Camera controls and view commands only affect this camera.
+#[derive(Component)]
+pub struct ActiveCamera;
+
+/// Smoothly moves a [`Flycam`] to a new transform.
+/// Removed when the animation finishes or the camera is moved manually.
+#[derive(Component)]
+pub struct FlycamTarget {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+#[derive(Event)]
+pub enum FrameEntities {
+    /// The selected entities and their descendants
+    Selected,
+    /// Every entity in the scene
+    All,
+}
+
+/// Moves the active camera to look along an axis with an orthographic projection
+#[derive(Event)]
+pub struct AlignView(pub ViewDirection);
+
+/// Switches the active camera between a perspective and orthographic projection
+#[derive(Event)]
+pub struct ToggleOrthographic;
+
+/// A saved editor camera view
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    pub name: String,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub focus: [f32; 3],
+    pub orthographic: bool,
+    #[serde(default)]
+    pub view: Option<ViewDirection>,
+    /// The number that recalls it with Ctrl, from 1 to 9
+    #[serde(default)]
+    pub slot: Option<u8>,
+}
+
+#[derive(Resource, Default)]
+pub struct CameraBookmarks(pub Vec<CameraBookmark>);
+
+impl CameraBookmarks {
+    /// The index of the bookmark `key` refers to
+    pub fn position(&self, key: BookmarkKey) -> Option<usize> {
+        match key {
+            BookmarkKey::Index(index) => (index < self.0.len()).then_some(index),
+            BookmarkKey::Slot(slot) => self
+                .0
+                .iter()
+                .position(|bookmark| bookmark.slot == Some(slot)),
+        }
+    }
+}
+
+/// Which camera bookmark an event is about
+#[derive(Clone, Copy)]
+pub enum BookmarkKey {
+    /// The position in the bookmark list
+    Index(usize),
+    /// The Ctrl + number slot, from 1 to 9
+    Slot(u8),
+}
+
+/// Saves the active camera's view as a bookmark, replacing the bookmark `key` refers to.
+/// A new bookmark is added, in the slot if there is one, when none matches or `key` is `None`
+#[derive(Event)]
+pub struct StoreBookmark(pub Option<BookmarkKey>);
+
+/// Moves the active camera to a bookmark
+#[derive(Event)]
+pub struct RecallBookmark(pub BookmarkKey);
+
+/// Renders an editor camera's viewport through a scene camera
+#[derive(Component)]
+pub struct LookThrough {
+    pub camera: Entity,
+    /// Flycam controls move the scene camera
+    pub pilot: bool,
+    /// The editor camera's projection to restore afterwards
+    previous_projection: Projection,
+}
+
+/// Makes the active camera look through the selected scene camera, or stop looking through one
+#[derive(Event)]
+pub struct LookThroughSelected;
+
+/// Toggles moving the scene camera the active camera is looking through
+#[derive(Event)]
+pub struct TogglePilot;
+
+/// A camera for an editor viewport, cameras are rendered in `order`
+pub fn editor_camera(order: isize, transform: Transform) -> impl Bundle {
+    (
+        Camera3dBundle {
+            camera: Camera {
+                hdr: true,
+                order,
+                ..default()
+            },
+            transform,
+            ..default()
+        },
+        Flycam::default(),
+        EditorEntity,
+    )
+}
+
+/// Moves [`ActiveCamera`] to `camera`
+pub fn set_active_camera(world: &mut World, camera: Entity) {
+    if world.get::<ActiveCamera>(camera).is_some() {
+        return;
+    }
+
+    let active = world
+        .query_filtered::<Entity, With<ActiveCamera>>()
+        .iter(world)
+        .collect::<Vec<Entity>>();
+    for entity in active {
+        world.entity_mut(entity).remove::<ActiveCamera>();
+    }
+
+    world.entity_mut(camera).insert(ActiveCamera);
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((Observer::new(frame_entities), EditorEntity));
+    commands.spawn((Observer::new(align_view), EditorEntity));
+    commands.spawn((Observer::new(toggle_orthographic), EditorEntity));
+    commands.spawn((Observer::new(store_bookmark), EditorEntity));
+    commands.spawn((Observer::new(recall_bookmark), EditorEntity));
+    commands.spawn((Observer::new(look_through_selected), EditorEntity));
+    commands.spawn((Observer::new(toggle_pilot), EditorEntity));
+}
+
 fn update(
     time: Res<Time>,
     keys: Res<ButtonInput<KeyCode>>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     mut mouse_motion: EventReader<MouseMotion>,
-    mut query: Query<(&Flycam, &mut Transform)>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut query: Query<(&mut Flycam, &mut Transform), With<ActiveCamera>>,
     mut window_query: Query<&mut Window, With<PrimaryWindow>>,
     mut editor_action: ResMut<EditorAction>,
 ) {
@@ -63,6 +284,7 @@ fn update(
     let mouse_delta = mouse_motion
         .read()
         .fold(Vec2::ZERO, |o, m| o - m.delta.yx());
+    let scroll = scroll_delta(&mut mouse_wheel);
 
     let forward = keys.pressed(KeyCode::KeyW) as i8 - keys.pressed(KeyCode::KeyS) as i8;
     let right = keys.pressed(KeyCode::KeyD) as i8 - keys.pressed(KeyCode::KeyA) as i8;
@@ -70,8 +292,16 @@ fn update(
     let movement = Vec3::new(right as f32, up as f32, forward as f32) * time.delta_seconds();
 
     // Apply transform
-    for (flycam, mut transform) in query.iter_mut() {
+    for (mut flycam, mut transform) in query.iter_mut() {
+        // Scrolling while flying changes the speed
+        flycam.speed = (flycam.speed * 1.2f32.powf(scroll)).clamp(0.1, 1000.0);
+
         // Rotation
+        if mouse_delta != Vec2::ZERO {
+            flycam.view = None;
+        }
+
+        let focus_distance = transform.translation.distance(flycam.focus);
         let (ry, rx, _rz) = transform.rotation.to_euler(EulerRot::YXZ);
         transform.rotation = Quat::from_euler(
             EulerRot::YXZ,
@@ -86,14 +316,475 @@ fn update(
             + transform.forward() * movement.z)
             * flycam.speed;
         transform.translation += translation_delta;
+
+        // Keep the focus in front of the camera so orbiting continues from the new view
+        flycam.focus = transform.translation + transform.forward() * focus_distance;
     }
 
     editor_action.0 = Some(crate::CAMERA_ACTION_ID);
 }
 
+fn orbit(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    selected: Res<SelectedEntities>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut query: Query<(&mut Flycam, &mut Transform, &Projection), With<ActiveCamera>>,
+    global_query: Query<&GlobalTransform>,
+    mut editor_action: ResMut<EditorAction>,
+) {
+    // Zoom, orthographic cameras always zoom since moving forward doesn't change the view
+    let scroll = scroll_delta(&mut mouse_wheel);
+    if scroll != 0.0 && editor_action.is_none() {
+        for (flycam, mut transform, projection) in query.iter_mut() {
+            let is_orthographic = matches!(projection, Projection::Orthographic(_));
+            if flycam.mode != CameraMode::Orbit && !is_orthographic {
+                continue;
+            }
+
+            let offset = transform.translation - flycam.focus;
+            let distance = (offset.length() * 0.85f32.powf(scroll)).max(0.05);
+            transform.translation =
+                flycam.focus + offset.normalize_or(*transform.back()) * distance;
+        }
+    }
+
+    if mouse_button.just_pressed(MouseButton::Middle) && editor_action.is_none() {
+        editor_action.0 = Some(crate::ORBIT_ACTION_ID);
+    } else if !editor_action.is_some_and(|v| v == crate::ORBIT_ACTION_ID) {
+        return;
+    }
+
+    if !mouse_button.pressed(MouseButton::Middle) {
+        editor_action.0 = None;
+        return;
+    }
+
+    let mouse_delta = mouse_motion.read().fold(Vec2::ZERO, |o, m| o + m.delta);
+    let is_panning = keys.pressed(KeyCode::ShiftLeft);
+
+    // Only entities with a transform count, without any the focus stays where it is
+    let (sum, count) = selected
+        .0
+        .iter()
+        .filter_map(|entity| global_query.get(*entity).ok())
+        .fold((Vec3::ZERO, 0), |(sum, count), t| {
+            (sum + t.translation(), count + 1)
+        });
+    let selection_center = (count > 0).then(|| sum / count as f32);
+
+    for (mut flycam, mut transform, _projection) in query.iter_mut() {
+        if flycam.mode != CameraMode::Orbit {
+            continue;
+        }
+
+        if is_panning {
+            let distance = transform.translation.distance(flycam.focus).max(1.0);
+            let offset = (transform.left() * mouse_delta.x + transform.up() * mouse_delta.y)
+                * flycam.sensitivity
+                * distance
+                * 0.2;
+
+            transform.translation += offset;
+            flycam.focus += offset;
+            continue;
+        }
+
+        if let Some(center) = selection_center.filter(|_| flycam.orbit_selection) {
+            flycam.focus = center;
+        }
+
+        let yaw = Quat::from_rotation_y(-mouse_delta.x * flycam.sensitivity);
+        let mut pitch = -mouse_delta.y * flycam.sensitivity;
+
+        // Don't pitch past straight up or down
+        let (_ry, rx, _rz) = transform.rotation.to_euler(EulerRot::YXZ);
+        pitch = (rx + pitch).clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01) - rx;
+
+        let rotation = yaw * Quat::from_axis_angle(*transform.right(), pitch);
+        let focus = flycam.focus;
+        transform.rotate_around(focus, rotation);
+
+        if mouse_delta != Vec2::ZERO {
+            flycam.view = None;
+        }
+    }
+}
+
+fn animate(
+    time: Res<Time>,
+    editor_action: Res<EditorAction>,
+    mut query: Query<(Entity, &FlycamTarget, &mut Transform)>,
+    mut commands: Commands,
+) {
+    let is_moving =
+        editor_action.is_some_and(|v| v == crate::CAMERA_ACTION_ID || v == crate::ORBIT_ACTION_ID);
+    let t = 1.0 - (-12.0 * time.delta_seconds()).exp();
+
+    for (entity, target, mut transform) in query.iter_mut() {
+        if is_moving {
+            commands.entity(entity).remove::<FlycamTarget>();
+            continue;
+        }
+
+        transform.translation = transform.translation.lerp(target.translation, t);
+        transform.rotation = transform.rotation.slerp(target.rotation, t);
+
+        if transform.translation.distance(target.translation) < 0.001
+            && transform.rotation.angle_between(target.rotation) < 0.001
+        {
+            transform.translation = target.translation;
+            transform.rotation = target.rotation;
+            commands.entity(entity).remove::<FlycamTarget>();
+        }
+    }
+}
+
+fn frame_entities(
+    trigger: Trigger<FrameEntities>,
+    selected: Res<SelectedEntities>,
+    roots: Query<Entity, (Without<Parent>, Without<EditorEntity>)>,
+    children_query: Query<&Children>,
+    bounds_query: Query<(&GlobalTransform, Option<&Aabb>), Without<EditorEntity>>,
+    mut camera_query: Query<(Entity, &mut Flycam, &Transform, &Projection), With<ActiveCamera>>,
+    mut commands: Commands,
+) {
+    let entities: Vec<Entity> = match trigger.event() {
+        FrameEntities::Selected => selected.0.iter().copied().collect(),
+        FrameEntities::All => roots.iter().collect(),
+    };
+
+    let mut min = Vec3::INFINITY;
+    let mut max = Vec3::NEG_INFINITY;
+    for &entity in entities.iter() {
+        for entity in std::iter::once(entity).chain(children_query.iter_descendants(entity)) {
+            let Ok((global_transform, aabb)) = bounds_query.get(entity) else {
+                continue;
+            };
+
+            let (entity_min, entity_max) = match aabb {
+                Some(aabb) => crate::world_bounds(aabb, global_transform),
+                None => (
+                    global_transform.translation(),
+                    global_transform.translation(),
+                ),
+            };
+            min = min.min(entity_min);
+            max = max.max(entity_max);
+        }
+    }
+
+    if min.cmpgt(max).any() {
+        info!("There is nothing to frame.");
+        return;
+    }
+
+    let center = (min + max) / 2.0;
+    let radius = (max.distance(min) / 2.0).max(0.5);
+
+    for (entity, mut flycam, transform, projection) in camera_query.iter_mut() {
+        let distance = match projection {
+            Projection::Perspective(perspective) => {
+                // Fit the bounding sphere in the narrowest field of view
+                let horizontal_fov =
+                    2.0 * ((perspective.fov / 2.0).tan() * perspective.aspect_ratio).atan();
+                let fov = perspective.fov.min(horizontal_fov);
+                radius / (fov / 2.0).sin()
+            }
+            Projection::Orthographic(_) => radius / (FRAC_PI_4 / 2.0).tan(),
+        };
+
+        flycam.focus = center;
+        commands.entity(entity).insert(FlycamTarget {
+            translation: center - transform.forward() * distance,
+            rotation: transform.rotation,
+        });
+    }
+}
+
+fn align_view(
+    trigger: Trigger<AlignView>,
+    mut query: Query<(Entity, &mut Flycam, &Transform, &mut Projection), With<ActiveCamera>>,
+    mut commands: Commands,
+) {
+    let direction = trigger.event().0;
+
+    for (entity, mut flycam, transform, mut projection) in query.iter_mut() {
+        let distance = transform.translation.distance(flycam.focus).max(1.0);
+        let target = Transform::from_translation(flycam.focus + direction.offset() * distance)
+            .looking_at(flycam.focus, direction.up());
+
+        flycam.view = Some(direction);
+        if let Projection::Perspective(_) = *projection {
+            *projection = orthographic(distance);
+        }
+
+        commands.entity(entity).insert(FlycamTarget {
+            translation: target.translation,
+            rotation: target.rotation,
+        });
+    }
+}
+
+fn toggle_orthographic(
+    _trigger: Trigger<ToggleOrthographic>,
+    mut query: Query<(&Flycam, &Transform, &mut Projection), With<ActiveCamera>>,
+) {
+    for (flycam, transform, mut projection) in query.iter_mut() {
+        *projection = match *projection {
+            Projection::Perspective(_) => {
+                orthographic(transform.translation.distance(flycam.focus))
+            }
+            Projection::Orthographic(_) => {
+                Projection::Perspective(PerspectiveProjection::default())
+            }
+        };
+    }
+}
+
+fn store_bookmark(
+    trigger: Trigger<StoreBookmark>,
+    query: Query<(&Flycam, &Transform, &Projection), With<ActiveCamera>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+) {
+    let Ok((flycam, transform, projection)) = query.get_single() else {
+        return;
+    };
+
+    let key = trigger.event().0;
+    let bookmark = |name: String, slot: Option<u8>| CameraBookmark {
+        name,
+        translation: transform.translation.to_array(),
+        rotation: transform.rotation.to_array(),
+        focus: flycam.focus.to_array(),
+        orthographic: matches!(projection, Projection::Orthographic(_)),
+        view: flycam.view,
+        slot,
+    };
+
+    match key.and_then(|key| bookmarks.position(key)) {
+        Some(index) => {
+            let previous = &bookmarks.0[index];
+            let updated = bookmark(previous.name.clone(), previous.slot);
+            bookmarks.0[index] = updated;
+        }
+        None => {
+            let slot = match key {
+                Some(BookmarkKey::Slot(slot)) => Some(slot),
+                _ => None,
+            };
+            let name = format!("Bookmark {}", bookmarks.0.len() + 1);
+            bookmarks.0.push(bookmark(name, slot));
+        }
+    }
+}
+
+fn recall_bookmark(
+    trigger: Trigger<RecallBookmark>,
+    bookmarks: Res<CameraBookmarks>,
+    mut query: Query<(Entity, &mut Flycam, &mut Projection), With<ActiveCamera>>,
+    mut commands: Commands,
+) {
+    let key = trigger.event().0;
+    let Some(bookmark) = bookmarks.position(key).map(|index| &bookmarks.0[index]) else {
+        match key {
+            BookmarkKey::Index(index) => info!("Camera bookmark {} doesn't exist.", index + 1),
+            BookmarkKey::Slot(slot) => info!("No camera bookmark is in slot {slot}."),
+        }
+        return;
+    };
+
+    let translation = Vec3::from_array(bookmark.translation);
+    let focus = Vec3::from_array(bookmark.focus);
+
+    for (entity, mut flycam, mut projection) in query.iter_mut() {
+        flycam.focus = focus;
+        flycam.view = bookmark.view;
+
+        match (bookmark.orthographic, &*projection) {
+            (true, Projection::Perspective(_)) => {
+                *projection = orthographic(translation.distance(focus));
+            }
+            (false, Projection::Orthographic(_)) => {
+                *projection = Projection::Perspective(PerspectiveProjection::default());
+            }
+            _ => {}
+        }
+
+        commands.entity(entity).insert(FlycamTarget {
+            translation,
+            rotation: Quat::from_array(bookmark.rotation),
+        });
+    }
+}
+
+fn look_through_selected(
+    _trigger: Trigger<LookThroughSelected>,
+    selected: Res<SelectedEntities>,
+    scene_cameras: Query<&Projection, (With<Camera>, Without<EditorEntity>)>,
+    mut query: Query<
+        (Entity, &mut Projection, Option<&LookThrough>),
+        (With<ActiveCamera>, With<EditorEntity>),
+    >,
+    mut commands: Commands,
+) {
+    let Ok((entity, mut projection, look_through)) = query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(look_through) = look_through {
+        *projection = look_through.previous_projection.clone();
+        commands.entity(entity).remove::<LookThrough>();
+        return;
+    }
+
+    let Some((camera, camera_projection)) = selected
+        .0
+        .iter()
+        .rev()
+        .find_map(|&entity| Some((entity, scene_cameras.get(entity).ok()?)))
+    else {
+        info!("No camera is selected.");
+        return;
+    };
+
+    let previous_projection = std::mem::replace(&mut *projection, camera_projection.clone());
+    commands.entity(entity).insert(LookThrough {
+        camera,
+        pilot: false,
+        previous_projection,
+    });
+}
+
+fn toggle_pilot(
+    _trigger: Trigger<TogglePilot>,
+    mut query: Query<&mut LookThrough, With<ActiveCamera>>,
+) {
+    let Ok(mut look_through) = query.get_single_mut() else {
+        info!("The active camera isn't looking through a camera.");
+        return;
+    };
+
+    look_through.pilot = !look_through.pilot;
+}
+
+/// Keeps editor cameras at the scene camera they're looking through,
+/// or moves the scene camera with the editor camera while piloting
+fn update_look_through(
+    editor_action: Res<EditorAction>,
+    mut editor_query: Query<(Entity, &mut Transform, &mut Projection, &LookThrough), With<Flycam>>,
+    mut scene_query: Query<(&mut Transform, Ref<Projection>, Option<&Parent>), Without<Flycam>>,
+    global_query: Query<&GlobalTransform>,
+    mut commands: Commands,
+) {
+    let is_moving =
+        editor_action.is_some_and(|v| v == crate::CAMERA_ACTION_ID || v == crate::ORBIT_ACTION_ID);
+
+    for (entity, mut transform, mut projection, look_through) in editor_query.iter_mut() {
+        let Ok((mut camera_transform, camera_projection, parent)) =
+            scene_query.get_mut(look_through.camera)
+        else {
+            // The scene camera was removed
+            *projection = look_through.previous_projection.clone();
+            commands.entity(entity).remove::<LookThrough>();
+            continue;
+        };
+
+        if camera_projection.is_changed() {
+            *projection = (*camera_projection).clone();
+        }
+
+        if look_through.pilot {
+            let parent_transform = parent
+                .and_then(|parent| global_query.get(parent.get()).ok())
+                .copied()
+                .unwrap_or_default();
+            *camera_transform = GlobalTransform::from(*transform).reparented_to(&parent_transform);
+        } else if is_moving {
+            // Moving the view without piloting leaves the scene camera
+            *projection = look_through.previous_projection.clone();
+            commands.entity(entity).remove::<LookThrough>();
+        } else if let Ok(global_transform) = global_query.get(look_through.camera) {
+            *transform = global_transform.compute_transform();
+        }
+    }
+}
+
+/// An orthographic projection showing the same area as a perspective camera
+/// at `distance` from its focus
+fn orthographic(distance: f32) -> Projection {
+    Projection::Orthographic(OrthographicProjection {
+        near: -1000.0,
+        far: 1000.0,
+        scaling_mode: ScalingMode::FixedVertical(2.0),
+        scale: orthographic_scale(distance),
+        ..default()
+    })
+}
+
+fn orthographic_scale(distance: f32) -> f32 {
+    distance * (FRAC_PI_4 / 2.0).tan()
+}
+
+/// Orthographic cameras zoom by scale instead of distance
+fn update_orthographic_scale(
+    mut query: Query<(&Flycam, &Transform, &mut Projection), Without<LookThrough>>,
+) {
+    for (flycam, transform, mut projection) in query.iter_mut() {
+        if let Projection::Orthographic(orthographic) = &mut *projection {
+            orthographic.scale = orthographic_scale(transform.translation.distance(flycam.focus));
+        }
+    }
+}
+
+/// Sums the vertical scroll of all [`MouseWheel`] events in lines
+fn scroll_delta(mouse_wheel: &mut EventReader<MouseWheel>) -> f32 {
+    mouse_wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y * 0.01,
+        })
+        .sum()
+}
+
+/// Adds volumetric fog to the editor cameras while a scene light shines through it,
+/// and removes it with the last such light. Scene cameras are left as they are.
+/// Bevy's fog fills the view of a camera, bounded fog volumes need Bevy 0.15
+fn update_volumetric_fog(
+    light_query: Query<(), (With<VolumetricLight>, Without<EditorEntity>)>,
+    camera_query: Query<(Entity, Has<VolumetricFogSettings>), With<Flycam>>,
+    mut commands: Commands,
+) {
+    let fog = !light_query.is_empty();
+    for (camera, has_fog) in camera_query.iter() {
+        if fog && !has_fog {
+            commands
+                .entity(camera)
+                .insert(VolumetricFogSettings::default());
+        } else if !fog && has_fog {
+            commands.entity(camera).remove::<VolumetricFogSettings>();
+        }
+    }
+}
+
 pub struct FlycamPlugin;
 impl Plugin for FlycamPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update);
+        app.insert_resource(CameraBookmarks::default());
+        app.add_systems(PreStartup, setup);
+        app.add_systems(
+            Update,
+            (
+                update,
+                orbit,
+                animate,
+                update_look_through,
+                update_orthographic_scale,
+            )
+                .chain(),
+        );
+        app.add_systems(Update, update_volumetric_fog);
     }
 }
@@ -1,45 +1,274 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::system::SystemParam,
+    pbr::{irradiance_volume::IrradianceVolume, VolumetricLight},
+    prelude::*,
+};
 
-use crate::observers::SelectEntity;
+use crate::{
+    camera::{ActiveCamera, Flycam},
+    observers::{Reparent, SelectEntity},
+    picking::Cursor3d,
+    primitive::Primitive,
+    SelectedEntities,
+};
 
-pub fn mesh<M: Into<Mesh> + TypePath + Default>(
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+/// How far in front of the camera entities are placed when the cursor isn't set
+const CAMERA_SPAWN_DISTANCE: f32 = 5.0;
+
+/// Where new entities are placed, the 3D cursor or in front of the active camera
+#[derive(SystemParam)]
+pub struct SpawnPoint<'w, 's> {
+    cursor: Res<'w, Cursor3d>,
+    camera_query: Query<'w, 's, &'static GlobalTransform, (With<Flycam>, With<ActiveCamera>)>,
+}
+
+impl SpawnPoint<'_, '_> {
+    pub fn translation(&self) -> Vec3 {
+        if let Some(position) = self.cursor.position {
+            return position;
+        }
+
+        self.camera_query
+            .get_single()
+            .map(|global_transform| {
+                global_transform.translation() + global_transform.forward() * CAMERA_SPAWN_DISTANCE
+            })
+            .unwrap_or_default()
+    }
+
+    /// A transform at the spawn point moved by `offset`
+    pub fn transform(&self, offset: Vec3) -> Transform {
+        Transform::from_translation(self.translation() + offset)
+    }
+}
+
+/// Spawns an editable [`Primitive`] shape, its mesh is generated by the primitive
+pub fn primitive(
+    primitive: Primitive,
+) -> impl FnMut(SpawnPoint, ResMut<Assets<StandardMaterial>>, Commands) + Send + Sync + 'static {
+    move |spawn_point: SpawnPoint,
+          mut materials: ResMut<Assets<StandardMaterial>>,
+          mut commands: Commands| {
+        let target = commands
+            .spawn((
+                PbrBundle {
+                    material: materials.add(StandardMaterial::default()),
+                    transform: spawn_point.transform(Vec3::ZERO),
+                    ..default()
+                },
+                primitive.clone(),
+                Name::new(primitive.name()),
+            ))
+            .id();
+        commands.trigger(SelectEntity { target });
+    }
+}
+
+/// Spawns an entity with only a transform, to parent other entities to
+pub fn empty(spawn_point: SpawnPoint, mut commands: Commands) {
+    let target = commands
+        .spawn((
+            SpatialBundle::from_transform(spawn_point.transform(Vec3::ZERO)),
+            Name::new("Empty"),
+        ))
+        .id();
+    commands.trigger(SelectEntity { target });
+}
+
+/// Parents the selected entities to a new empty at their center
+pub fn group(
+    mut selected: ResMut<SelectedEntities>,
+    global_query: Query<&GlobalTransform>,
+    parent_query: Query<&Parent>,
     mut commands: Commands,
 ) {
-    let mut entity_cmds = commands.spawn(PbrBundle {
-        mesh: meshes.add(M::default()),
-        material: materials.add(StandardMaterial::default()),
-        ..default()
-    });
-
-    if let Some(ident) = M::type_ident() {
-        entity_cmds.insert(Name::new(ident));
+    // Selected descendants stay in their selected ancestor
+    let roots = selected
+        .0
+        .iter()
+        .filter(|&&entity| {
+            !parent_query
+                .iter_ancestors(entity)
+                .any(|ancestor| selected.0.contains(&ancestor))
+        })
+        .cloned()
+        .collect::<Vec<Entity>>();
+
+    let positions = roots
+        .iter()
+        .filter_map(|&entity| global_query.get(entity).ok())
+        .map(|global_transform| global_transform.translation())
+        .collect::<Vec<Vec3>>();
+    if positions.is_empty() {
+        info!("No entity is selected.");
+        return;
     }
 
-    let target = entity_cmds.id();
+    // The group shares the parent of the first entity
+    let parent = parent_query.get(roots[0]).ok().map(|parent| parent.get());
+    let transform =
+        Transform::from_translation(positions.iter().sum::<Vec3>() / positions.len() as f32);
+
+    // The global transform is set up front, as reparenting reads it before it's propagated
+    let group = commands
+        .spawn((
+            SpatialBundle {
+                transform,
+                global_transform: transform.into(),
+                ..default()
+            },
+            Name::new("Group"),
+        ))
+        .id();
+
+    if let Some(parent) = parent {
+        commands.trigger(Reparent {
+            entity: group,
+            parent: Some(parent),
+            index: None,
+        });
+    }
+    for entity in roots {
+        commands.trigger(Reparent {
+            entity,
+            parent: Some(group),
+            index: None,
+        });
+    }
+
+    selected.0.clear();
+    selected.0.insert(group);
+}
+
+pub fn camera(spawn_point: SpawnPoint, mut commands: Commands) {
+    // Looking at the spawn point from a bit above and behind it
+    let target_point = spawn_point.translation() + Vec3::Y;
+    let target = commands
+        .spawn((
+            Camera3dBundle {
+                // Inactive so it doesn't render over the editor viewports
+                camera: Camera {
+                    is_active: false,
+                    ..default()
+                },
+                transform: spawn_point
+                    .transform(Vec3::new(0.0, 1.5, 5.0))
+                    .looking_at(target_point, Vec3::Y),
+                ..default()
+            },
+            Name::new("Camera"),
+        ))
+        .id();
+    commands.trigger(SelectEntity { target });
+}
+
+pub fn point_light(spawn_point: SpawnPoint, mut commands: Commands) {
+    let target = commands
+        .spawn((
+            PointLightBundle {
+                transform: spawn_point.transform(Vec3::new(0.0, 2.0, 0.0)),
+                ..default()
+            },
+            Name::new("Point Light"),
+        ))
+        .id();
+    commands.trigger(SelectEntity { target });
+}
+
+pub fn spot_light(spawn_point: SpawnPoint, mut commands: Commands) {
+    let target = commands
+        .spawn((
+            SpotLightBundle {
+                transform: spawn_point
+                    .transform(Vec3::new(0.0, 3.0, 0.0))
+                    .looking_to(Vec3::NEG_Y, Vec3::Z),
+                ..default()
+            },
+            Name::new("Spot Light"),
+        ))
+        .id();
     commands.trigger(SelectEntity { target });
 }
 
-pub fn point_light(mut commands: Commands) {
+pub fn directional_light(spawn_point: SpawnPoint, mut commands: Commands) {
     let target = commands
-        .spawn((PointLightBundle::default(), Name::new("PointLight")))
+        .spawn((
+            DirectionalLightBundle {
+                transform: spawn_point
+                    .transform(Vec3::new(0.0, 5.0, 0.0))
+                    .looking_to(Vec3::new(-1.0, -2.0, -1.0), Vec3::Y),
+                ..default()
+            },
+            Name::new("Directional Light"),
+        ))
         .id();
     commands.trigger(SelectEntity { target });
 }
 
-pub fn spot_light(mut commands: Commands) {
+/// Spawns a directional light that shines through volumetric fog.
+/// The editor viewports show the fog while such a light exists, see `update_volumetric_fog`
+pub fn volumetric_fog(spawn_point: SpawnPoint, mut commands: Commands) {
     let target = commands
-        .spawn((SpotLightBundle::default(), Name::new("SpotLight")))
+        .spawn((
+            DirectionalLightBundle {
+                // Only lights with shadow maps light the fog
+                directional_light: DirectionalLight {
+                    shadows_enabled: true,
+                    ..default()
+                },
+                transform: spawn_point
+                    .transform(Vec3::new(0.0, 5.0, 0.0))
+                    .looking_to(Vec3::new(-1.0, -2.0, -1.0), Vec3::Y),
+                ..default()
+            },
+            VolumetricLight,
+            Name::new("Volumetric Light"),
+        ))
+        .id();
+    commands.trigger(SelectEntity { target });
+}
+
+/// The size of new light probes, their bounds are a unit cube scaled by the transform
+const LIGHT_PROBE_SIZE: f32 = 10.0;
+
+/// Spawns a reflection probe without maps, they are set in the inspector
+pub fn reflection_probe(spawn_point: SpawnPoint, mut commands: Commands) {
+    let target = commands
+        .spawn((
+            ReflectionProbeBundle {
+                spatial: SpatialBundle::from_transform(
+                    spawn_point
+                        .transform(Vec3::new(0.0, LIGHT_PROBE_SIZE * 0.5, 0.0))
+                        .with_scale(Vec3::splat(LIGHT_PROBE_SIZE)),
+                ),
+                light_probe: LightProbe,
+                environment_map: EnvironmentMapLight {
+                    diffuse_map: default(),
+                    specular_map: default(),
+                    intensity: 1000.0,
+                },
+            },
+            Name::new("Reflection Probe"),
+        ))
         .id();
     commands.trigger(SelectEntity { target });
 }
 
-pub fn directional_light(mut commands: Commands) {
+/// Spawns an irradiance volume without voxels, they are set in the inspector
+pub fn irradiance_volume(spawn_point: SpawnPoint, mut commands: Commands) {
     let target = commands
         .spawn((
-            DirectionalLightBundle::default(),
-            Name::new("DirectionalLight"),
+            SpatialBundle::from_transform(
+                spawn_point
+                    .transform(Vec3::new(0.0, LIGHT_PROBE_SIZE * 0.5, 0.0))
+                    .with_scale(Vec3::splat(LIGHT_PROBE_SIZE)),
+            ),
+            LightProbe,
+            IrradianceVolume {
+                voxels: default(),
+                intensity: 1000.0,
+            },
+            Name::new("Irradiance Volume"),
         ))
         .id();
     commands.trigger(SelectEntity { target });
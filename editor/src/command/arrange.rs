@@ -0,0 +1,211 @@
+use bevy::{ecs::system::SystemParam, prelude::*, render::primitives::Aabb, utils::HashSet};
+
+use crate::{world_bounds, EditorEntity, Locked, SelectedEntities};
+
+#[derive(Clone, Copy, Debug)]
+pub enum AlignEdge {
+    Min,
+    Center,
+    Max,
+}
+
+impl AlignEdge {
+    pub const ALL: [AlignEdge; 3] = [AlignEdge::Min, AlignEdge::Center, AlignEdge::Max];
+
+    fn value(&self, (min, max): (Vec3, Vec3), axis: usize) -> f32 {
+        match self {
+            AlignEdge::Min => min[axis],
+            AlignEdge::Center => (min[axis] + max[axis]) * 0.5,
+            AlignEdge::Max => max[axis],
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Arrange<'w, 's> {
+    selected: Res<'w, SelectedEntities>,
+    bounds_query: Query<'w, 's, (&'static GlobalTransform, Option<&'static Aabb>)>,
+    surface_query:
+        Query<'w, 's, (Entity, &'static GlobalTransform, &'static Aabb), Without<EditorEntity>>,
+    children_query: Query<'w, 's, &'static Children>,
+    parent_query: Query<'w, 's, &'static Parent>,
+    transform_query: Query<'w, 's, &'static mut Transform, Without<Locked>>,
+}
+
+impl Arrange<'_, '_> {
+    /// The selected entities that can move, without their selected descendants
+    fn targets(&self) -> Vec<Entity> {
+        self.selected
+            .0
+            .iter()
+            .filter(|&&entity| {
+                self.transform_query.contains(entity)
+                    && !self
+                        .parent_query
+                        .iter_ancestors(entity)
+                        .any(|ancestor| self.selected.0.contains(&ancestor))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The world space bounds of an entity and its descendants,
+    /// entities without an [`Aabb`] are a point
+    fn bounds(&self, entity: Entity) -> Option<(Vec3, Vec3)> {
+        let (global_transform, _) = self.bounds_query.get(entity).ok()?;
+        let mut bounds: Option<(Vec3, Vec3)> = None;
+
+        for entity in std::iter::once(entity).chain(self.children_query.iter_descendants(entity)) {
+            let Ok((global_transform, Some(aabb))) = self.bounds_query.get(entity) else {
+                continue;
+            };
+
+            let (min, max) = world_bounds(aabb, global_transform);
+            bounds = Some(match bounds {
+                Some((bounds_min, bounds_max)) => (bounds_min.min(min), bounds_max.max(max)),
+                None => (min, max),
+            });
+        }
+
+        let translation = global_transform.translation();
+        Some(bounds.unwrap_or((translation, translation)))
+    }
+
+    fn union_bounds(&self, entities: &[Entity]) -> Option<(Vec3, Vec3)> {
+        entities
+            .iter()
+            .filter_map(|&entity| self.bounds(entity))
+            .reduce(|(a_min, a_max), (b_min, b_max)| (a_min.min(b_min), a_max.max(b_max)))
+    }
+
+    /// Moves an entity by a world space offset
+    fn translate(&mut self, entity: Entity, delta: Vec3) {
+        let parent_transform = self
+            .parent_query
+            .get(entity)
+            .ok()
+            .and_then(|parent| self.bounds_query.get(parent.get()).ok())
+            .map(|(global_transform, _)| *global_transform)
+            .unwrap_or_default();
+
+        if let Ok(mut transform) = self.transform_query.get_mut(entity) {
+            transform.translation += parent_transform.affine().inverse().transform_vector3(delta);
+        }
+    }
+}
+
+/// Aligns an edge of the selected entities on `axis` to the selection bounds,
+/// or to the last selected (active) entity
+pub fn align(
+    edge: AlignEdge,
+    axis: usize,
+    to_active: bool,
+) -> impl FnMut(Arrange) + Send + Sync + 'static {
+    move |mut arrange: Arrange| {
+        let targets = arrange.targets();
+        let active = arrange.selected.0.last().copied();
+
+        let reference = if to_active {
+            active.and_then(|active| arrange.bounds(active))
+        } else {
+            arrange.union_bounds(&targets)
+        };
+        let Some(reference) = reference else {
+            info!("No entity is selected.");
+            return;
+        };
+        let reference = edge.value(reference, axis);
+
+        for entity in targets {
+            if to_active && Some(entity) == active {
+                continue;
+            }
+            let Some(bounds) = arrange.bounds(entity) else {
+                continue;
+            };
+
+            let mut delta = Vec3::ZERO;
+            delta[axis] = reference - edge.value(bounds, axis);
+            arrange.translate(entity, delta);
+        }
+    }
+}
+
+/// Spaces the centers of the selected entities evenly on `axis`,
+/// keeping the first and last in place
+pub fn distribute(axis: usize) -> impl FnMut(Arrange) + Send + Sync + 'static {
+    move |mut arrange: Arrange| {
+        let mut centers = arrange
+            .targets()
+            .into_iter()
+            .filter_map(|entity| {
+                let bounds = arrange.bounds(entity)?;
+                Some((entity, AlignEdge::Center.value(bounds, axis)))
+            })
+            .collect::<Vec<(Entity, f32)>>();
+
+        if centers.len() < 3 {
+            info!("Distributing needs at least 3 entities.");
+            return;
+        }
+
+        centers.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let first = centers[0].1;
+        let step = (centers[centers.len() - 1].1 - first) / (centers.len() - 1) as f32;
+
+        for (i, &(entity, center)) in centers.iter().enumerate() {
+            let mut delta = Vec3::ZERO;
+            delta[axis] = first + step * i as f32 - center;
+            arrange.translate(entity, delta);
+        }
+    }
+}
+
+/// Moves the selected entities down so they rest on the ground plane
+pub fn drop_to_ground(mut arrange: Arrange) {
+    for entity in arrange.targets() {
+        let Some((min, _)) = arrange.bounds(entity) else {
+            continue;
+        };
+
+        arrange.translate(entity, Vec3::new(0.0, -min.y, 0.0));
+    }
+}
+
+/// Moves the selected entities down onto the bounds of the entities below them,
+/// or the ground plane if there are none
+pub fn drop_to_surface(mut arrange: Arrange) {
+    for entity in arrange.targets() {
+        let Some((min, max)) = arrange.bounds(entity) else {
+            continue;
+        };
+
+        let own = std::iter::once(entity)
+            .chain(arrange.children_query.iter_descendants(entity))
+            .collect::<HashSet<Entity>>();
+
+        let surface = arrange
+            .surface_query
+            .iter()
+            .filter(|(other, _, _)| !own.contains(other))
+            .map(|(_, global_transform, aabb)| world_bounds(aabb, global_transform))
+            .filter(|(other_min, other_max)| {
+                // Overlapping from above
+                other_min.x <= max.x
+                    && other_max.x >= min.x
+                    && other_min.z <= max.z
+                    && other_max.z >= min.z
+                    && other_max.y <= min.y + f32::EPSILON
+            })
+            .map(|(_, other_max)| other_max.y)
+            .reduce(f32::max);
+
+        let surface = match surface {
+            Some(surface) => surface,
+            None if min.y >= 0.0 => 0.0,
+            None => continue,
+        };
+
+        arrange.translate(entity, Vec3::new(0.0, surface - min.y, 0.0));
+    }
+}
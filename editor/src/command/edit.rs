@@ -0,0 +1,150 @@
+use std::any::TypeId;
+
+use bevy::{
+    ecs::entity::EntityHashMap, prelude::*, scene::serde::SceneDeserializer, utils::HashSet,
+};
+use bevy_egui::EguiClipboard;
+use serde::de::DeserializeSeed;
+
+use crate::{
+    interface::array::ArrayTool,
+    observers::{DeleteSelected, DuplicateSelected},
+    EditorEntity, SelectedEntities, UndoStack,
+};
+
+use super::scene;
+
+pub fn duplicate(mut commands: Commands) {
+    commands.trigger(DuplicateSelected);
+}
+
+/// Opens the array tool for the selection
+pub fn array(selected: Res<SelectedEntities>, mut commands: Commands) {
+    if selected.0.is_empty() {
+        info!("No entity is selected.");
+        return;
+    }
+
+    commands.init_resource::<ArrayTool>();
+}
+
+/// Copies the selected entities and their descendants to the clipboard as a scene
+pub fn copy(world: &mut World) {
+    copy_selection(world);
+}
+
+/// Returns whether the selection is on the clipboard
+fn copy_selection(world: &mut World) -> bool {
+    let selected = world.resource::<SelectedEntities>().0.clone();
+    if selected.is_empty() {
+        info!("No entity is selected.");
+        return false;
+    }
+
+    let mut entities = HashSet::new();
+    let mut stack = selected.into_iter().collect::<Vec<Entity>>();
+    while let Some(entity) = stack.pop() {
+        if world.get::<EditorEntity>(entity).is_some() || !entities.insert(entity) {
+            continue;
+        }
+
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter());
+        }
+    }
+
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .allow_all()
+        .extract_entities(entities.iter().cloned())
+        .build();
+
+    // Roots are pasted without a parent, so keep where they are in the world
+    for dynamic_entity in scene.entities.iter_mut() {
+        let Some(parent) = world.get::<Parent>(dynamic_entity.entity) else {
+            continue;
+        };
+        if entities.contains(&parent.get()) {
+            continue;
+        }
+
+        let global_transform = world.get::<GlobalTransform>(dynamic_entity.entity);
+        dynamic_entity.components.retain(|component| {
+            component
+                .get_represented_type_info()
+                .is_none_or(|info| info.type_id() != TypeId::of::<Parent>())
+        });
+
+        if let Some(global_transform) = global_transform {
+            for component in dynamic_entity.components.iter_mut() {
+                if component
+                    .get_represented_type_info()
+                    .is_some_and(|info| info.type_id() == TypeId::of::<Transform>())
+                {
+                    *component = Box::new(global_transform.compute_transform());
+                }
+            }
+        }
+    }
+
+    let serialized_scene = match scene::serialize(world, &scene) {
+        Ok(serialized_scene) => serialized_scene,
+        Err(e) => {
+            error!("Failed to serialize the selection.\n{e:?}");
+            return false;
+        }
+    };
+
+    world
+        .resource_mut::<EguiClipboard>()
+        .set_contents(&serialized_scene);
+    true
+}
+
+/// Copies the selection to the clipboard and deletes it, only once it's copied
+pub fn cut(world: &mut World) {
+    if copy_selection(world) {
+        world.trigger(DeleteSelected);
+    }
+}
+
+/// Spawns the scene in the clipboard and selects the pasted roots
+pub fn paste(world: &mut World) {
+    let Some(contents) = world.resource_mut::<EguiClipboard>().get_contents() else {
+        info!("The clipboard is empty.");
+        return;
+    };
+
+    let scene = match scene::deserialize(world, &contents) {
+        Ok(scene) => scene,
+        Err(e) => {
+            info!("The clipboard doesn't contain entities.");
+            debug!("{e:?}");
+            return;
+        }
+    };
+
+    // Entity ids and parent links are remapped to new entities
+    let mut entity_map = EntityHashMap::default();
+    if let Err(e) = scene.write_to_world(world, &mut entity_map) {
+        error!("Failed to paste entities.\n{e:?}");
+        return;
+    }
+
+    let roots = entity_map
+        .values()
+        .filter(|&&entity| world.get::<Parent>(entity).is_none())
+        .cloned()
+        .collect();
+    world.resource_mut::<SelectedEntities>().0 = roots;
+}
+
+/// Undoes the last action on the [`UndoStack`]
+pub fn undo(world: &mut World) {
+    let Some(record) = world.resource_mut::<UndoStack>().0.pop() else {
+        info!("Nothing to undo.");
+        return;
+    };
+
+    info!("Undo {}", record.name);
+    (record.undo)(world);
+}
@@ -0,0 +1,72 @@
+use std::fs::File;
+
+use bevy::{prelude::*, tasks::IoTaskPool};
+
+use crate::{gltf_export, EditorEntity, SelectedEntities};
+
+/// Whether an ancestor of `entity` is in `selected`
+fn has_selected_ancestor(world: &World, entity: Entity, selected: &SelectedEntities) -> bool {
+    let mut current = entity;
+    while let Some(parent) = world.get::<Parent>(current) {
+        if selected.0.contains(&parent.get()) {
+            return true;
+        }
+        current = parent.get();
+    }
+
+    false
+}
+
+/// Exports the selected entities and their descendants as a `.glb` file,
+/// or the whole scene when nothing is selected
+pub fn gltf(world: &mut World) {
+    let mut dialog = rfd::FileDialog::new().add_filter("Binary glTF", &["glb"]);
+    if let Ok(path) = std::env::current_dir() {
+        dialog = dialog.set_directory(path);
+    }
+
+    let Some(path) = dialog.save_file() else {
+        return;
+    };
+
+    let selected = world.resource::<SelectedEntities>();
+    let roots = if selected.0.is_empty() {
+        // Only spatial entities become nodes, like meshes, lights and cameras
+        world
+            .iter_entities()
+            .filter(|entity| {
+                entity.contains::<GlobalTransform>()
+                    && !entity.contains::<Parent>()
+                    && !entity.contains::<EditorEntity>()
+            })
+            .map(|entity| entity.id())
+            .collect::<Vec<_>>()
+    } else {
+        // Descendants are exported with their selected ancestor
+        selected
+            .0
+            .iter()
+            .copied()
+            .filter(|&entity| !has_selected_ancestor(world, entity, selected))
+            .collect()
+    };
+
+    let glb = match gltf_export::export(world, &roots) {
+        Ok(glb) => glb,
+        Err(e) => {
+            error!("Failed to export glTF.\n{e:?}");
+            return;
+        }
+    };
+
+    IoTaskPool::get()
+        .spawn(async move {
+            if let Err(e) = File::create(&path)
+                .map_err(gltf::Error::Io)
+                .and_then(|file| glb.to_writer(file))
+            {
+                error!("Failed to write glTF to file: {path:?}\n{e:?}");
+            }
+        })
+        .detach();
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use crate::{observers::Reparent, SelectedEntities};
+
+/// Parents the selected entities to the last selected (active) entity
+pub fn parent_to_active(selected: Res<SelectedEntities>, mut commands: Commands) {
+    let Some(&active) = selected.0.last() else {
+        info!("No entity is selected.");
+        return;
+    };
+
+    for &entity in selected.0.iter() {
+        if entity == active {
+            continue;
+        }
+
+        commands.trigger(Reparent {
+            entity,
+            parent: Some(active),
+            index: None,
+        });
+    }
+}
+
+/// Moves the selected entities to the root
+pub fn clear_parent(selected: Res<SelectedEntities>, mut commands: Commands) {
+    for &entity in selected.0.iter() {
+        commands.trigger(Reparent {
+            entity,
+            parent: None,
+            index: None,
+        });
+    }
+}
@@ -1,5 +1,14 @@
 use bevy::prelude::*;
 
+use crate::{
+    command::add::SpawnPoint,
+    interface::gltf_import::GltfImport,
+    mesh_file,
+    observers::SelectEntity,
+    vrm::{self, LoadingVrm, Vrm},
+};
+
+/// Opens the import popup for a glTF file
 pub fn gltf(asset_server: Res<AssetServer>, mut commands: Commands) {
     let mut dialog = rfd::FileDialog::new().add_filter("Gltf", &["gltf", "glb"]);
     if let Ok(path) = std::env::current_dir() {
@@ -10,23 +19,70 @@ pub fn gltf(asset_server: Res<AssetServer>, mut commands: Commands) {
         return;
     };
 
-    commands.spawn(SceneBundle {
-        scene: asset_server.load(GltfAssetLabel::Scene(0).from_asset(path)),
-        ..default()
+    let gltf = asset_server.load(path.clone());
+    commands.insert_resource(GltfImport::new(path, gltf));
+}
+
+/// Spawns a VRM avatar, its scene is spawned once the file loads
+pub fn vrm(asset_server: Res<AssetServer>, spawn_point: SpawnPoint, mut commands: Commands) {
+    let mut dialog = rfd::FileDialog::new().add_filter("Vrm", &["vrm"]);
+    if let Ok(path) = std::env::current_dir() {
+        dialog = dialog.set_directory(path);
+    }
+
+    let Some(path) = dialog.pick_file() else {
+        return;
+    };
+
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "VRM".to_string());
+
+    let target = commands
+        .spawn((
+            SpatialBundle::from_transform(spawn_point.transform(Vec3::ZERO)),
+            Name::new(name),
+            Vrm {
+                path: path.to_string_lossy().into(),
+                ..default()
+            },
+            LoadingVrm(vrm::load(&asset_server, path)),
+        ))
+        .id();
+    commands.trigger(SelectEntity { target });
+}
+
+/// Imports a mesh file at the spawn point, its meshes are added once it loads
+fn import_mesh_file(
+    filter: &str,
+    extensions: &[&str],
+    spawn_point: &SpawnPoint,
+    commands: &mut Commands,
+) {
+    let mut dialog = rfd::FileDialog::new().add_filter(filter, extensions);
+    if let Ok(path) = std::env::current_dir() {
+        dialog = dialog.set_directory(path);
+    }
+
+    let Some(path) = dialog.pick_file() else {
+        return;
+    };
+
+    let transform = spawn_point.transform(Vec3::ZERO);
+    commands.add(move |world: &mut World| {
+        mesh_file::import(world, &path, transform);
     });
 }
 
-// pub fn vrm(asset_server: Res<AssetServer>, mut commands: Commands) {
-//     let Some(path) = rfd::FileDialog::new()
-//         .add_filter("Vrm", &["vrm"])
-//         .pick_file()
-//     else {
-//         return;
-//     };
-
-//     commands.spawn(bevy_vrm::VrmBundle {
-//         scene_bundle: SceneBundle { ..default() },
-//         vrm: asset_server.load(path),
-//         ..default()
-//     });
-// }
+pub fn obj(spawn_point: SpawnPoint, mut commands: Commands) {
+    import_mesh_file("Obj", &["obj"], &spawn_point, &mut commands);
+}
+
+pub fn stl(spawn_point: SpawnPoint, mut commands: Commands) {
+    import_mesh_file("Stl", &["stl"], &spawn_point, &mut commands);
+}
+
+pub fn ply(spawn_point: SpawnPoint, mut commands: Commands) {
+    import_mesh_file("Ply", &["ply"], &spawn_point, &mut commands);
+}
@@ -1,11 +1,18 @@
-mod add;
+pub mod add;
+mod arrange;
+pub mod edit;
+mod export;
+mod hierarchy;
 mod import;
-mod scene;
+mod prefab;
+pub mod scene;
+mod view;
 
-use bevy::{ecs::system::SystemId, prelude::*};
+use bevy::{ecs::system::SystemId, pbr::irradiance_volume::IrradianceVolume, prelude::*};
 use indexmap::IndexMap;
 
-use crate::EditorEntity;
+use crate::{camera::ViewDirection, primitive::Primitive, EditorEntity};
+use arrange::AlignEdge;
 
 fn register_command<M, S: IntoSystem<(), (), M> + 'static>(
     world: &mut World,
@@ -112,68 +119,249 @@ impl Plugin for CommandPlugin {
             Some("File/Import"),
             import::gltf,
         );
-        // register_command(
-        //     app.world_mut(),
-        //     "Import VRM".into(),
-        //     ToolbarSection::Import,
-        //     import::vrm,
-        // );
+        register_command(
+            app.world_mut(),
+            "Vrm".into(),
+            Some("File/Import"),
+            import::vrm,
+        );
+        register_command(
+            app.world_mut(),
+            "Obj".into(),
+            Some("File/Import"),
+            import::obj,
+        );
+        register_command(
+            app.world_mut(),
+            "Stl".into(),
+            Some("File/Import"),
+            import::stl,
+        );
+        register_command(
+            app.world_mut(),
+            "Ply".into(),
+            Some("File/Import"),
+            import::ply,
+        );
+        register_command(
+            app.world_mut(),
+            "glTF".into(),
+            Some("File/Export"),
+            export::gltf,
+        );
+
+        register_command(
+            app.world_mut(),
+            "Duplicate".into(),
+            Some("Edit"),
+            edit::duplicate,
+        );
+        register_command(app.world_mut(), "Array".into(), Some("Edit"), edit::array);
+        register_command(app.world_mut(), "Copy".into(), Some("Edit"), edit::copy);
+        register_command(app.world_mut(), "Cut".into(), Some("Edit"), edit::cut);
+        register_command(app.world_mut(), "Paste".into(), Some("Edit"), edit::paste);
+        register_command(app.world_mut(), "Undo".into(), Some("Edit"), edit::undo);
+        register_command(
+            app.world_mut(),
+            "Create Prefab".into(),
+            Some("Edit/Prefab"),
+            prefab::create,
+        );
+        register_command(
+            app.world_mut(),
+            "Place Prefab".into(),
+            Some("Edit/Prefab"),
+            prefab::place,
+        );
+        register_command(
+            app.world_mut(),
+            "Apply To Prefab".into(),
+            Some("Edit/Prefab"),
+            prefab::apply,
+        );
+        register_command(
+            app.world_mut(),
+            "Revert Prefab Overrides".into(),
+            Some("Edit/Prefab"),
+            prefab::revert,
+        );
+
+        for (axis, axis_name) in ["X", "Y", "Z"].into_iter().enumerate() {
+            for edge in AlignEdge::ALL {
+                register_command(
+                    app.world_mut(),
+                    format!("Align {edge:?} {axis_name}"),
+                    Some("Edit/Align"),
+                    arrange::align(edge, axis, false),
+                );
+                register_command(
+                    app.world_mut(),
+                    format!("Align {edge:?} {axis_name} To Active"),
+                    Some("Edit/Align"),
+                    arrange::align(edge, axis, true),
+                );
+            }
+            register_command(
+                app.world_mut(),
+                format!("Distribute {axis_name}"),
+                Some("Edit/Align"),
+                arrange::distribute(axis),
+            );
+        }
+        register_command(
+            app.world_mut(),
+            "Drop To Ground".into(),
+            Some("Edit/Align"),
+            arrange::drop_to_ground,
+        );
+        register_command(
+            app.world_mut(),
+            "Drop To Surface".into(),
+            Some("Edit/Align"),
+            arrange::drop_to_surface,
+        );
+
+        register_command(
+            app.world_mut(),
+            "Parent To Active".into(),
+            Some("Edit"),
+            hierarchy::parent_to_active,
+        );
+        register_command(
+            app.world_mut(),
+            "Clear Parent".into(),
+            Some("Edit"),
+            hierarchy::clear_parent,
+        );
 
-        // Primitives
         register_command(
             app.world_mut(),
-            "Cube".into(),
-            Some("Add"),
-            add::mesh::<Cuboid>,
+            "Toggle Orbit Camera".into(),
+            Some("View"),
+            view::toggle_camera_mode,
         );
         register_command(
             app.world_mut(),
-            "Sphere".into(),
-            Some("Add"),
-            add::mesh::<Sphere>,
+            "Frame Selected".into(),
+            Some("View"),
+            view::frame_selected,
         );
         register_command(
             app.world_mut(),
-            "Plane".into(),
-            Some("Add"),
-            add::mesh::<Plane3d>,
+            "Frame All".into(),
+            Some("View"),
+            view::frame_all,
         );
         register_command(
             app.world_mut(),
-            "Cylinder".into(),
-            Some("Add"),
-            add::mesh::<Cylinder>,
+            "Toggle Orthographic".into(),
+            Some("View"),
+            view::toggle_orthographic,
         );
+        for direction in ViewDirection::ALL {
+            register_command(
+                app.world_mut(),
+                format!("{direction:?} View"),
+                Some("View/Align"),
+                view::align_view(direction),
+            );
+        }
+
         register_command(
             app.world_mut(),
-            "Cone".into(),
-            Some("Add"),
-            add::mesh::<Cone>,
+            "Cursor To Selected".into(),
+            Some("View"),
+            view::cursor_to_selected,
         );
         register_command(
             app.world_mut(),
-            "Torus".into(),
-            Some("Add"),
-            add::mesh::<Torus>,
+            "Clear Cursor".into(),
+            Some("View"),
+            view::clear_cursor,
         );
 
+        register_command(
+            app.world_mut(),
+            "Look Through Selected Camera".into(),
+            Some("View"),
+            view::look_through_selected,
+        );
+        register_command(
+            app.world_mut(),
+            "Pilot Camera".into(),
+            Some("View"),
+            view::toggle_pilot,
+        );
+
+        register_command(
+            app.world_mut(),
+            "Add Camera Bookmark".into(),
+            Some("View/Bookmarks"),
+            view::add_bookmark,
+        );
+        for slot in 1..=9 {
+            register_command(
+                app.world_mut(),
+                format!("Camera Bookmark {slot}"),
+                Some("View/Bookmarks"),
+                view::recall_bookmark(slot),
+            );
+        }
+
+        // The light probe components don't reflect `Component`,
+        // which saving, copying and the inspector need
+        app.register_type_data::<LightProbe, ReflectComponent>();
+        app.register_type_data::<EnvironmentMapLight, ReflectComponent>();
+        app.register_type_data::<IrradianceVolume, ReflectComponent>();
+
+        register_command(app.world_mut(), "Empty".into(), Some("Add"), add::empty);
+        register_command(app.world_mut(), "Group".into(), Some("Add"), add::group);
+        register_command(app.world_mut(), "Camera".into(), Some("Add"), add::camera);
+
+        for primitive in Primitive::ALL {
+            register_command(
+                app.world_mut(),
+                primitive.name().into(),
+                Some("Add/Mesh"),
+                add::primitive(primitive),
+            );
+        }
+
         register_command(
             app.world_mut(),
             "Directional Light".into(),
-            Some("Add"),
+            Some("Add/Light"),
             add::directional_light,
         );
         register_command(
             app.world_mut(),
             "Point Light".into(),
-            Some("Add"),
+            Some("Add/Light"),
             add::point_light,
         );
         register_command(
             app.world_mut(),
             "Spot Light".into(),
-            Some("Add"),
+            Some("Add/Light"),
             add::spot_light,
         );
+        register_command(
+            app.world_mut(),
+            "Reflection Probe".into(),
+            Some("Add/Light"),
+            add::reflection_probe,
+        );
+        register_command(
+            app.world_mut(),
+            "Irradiance Volume".into(),
+            Some("Add/Light"),
+            add::irradiance_volume,
+        );
+        register_command(
+            app.world_mut(),
+            "Volumetric Fog".into(),
+            Some("Add/Light"),
+            add::volumetric_fog,
+        );
     }
 }
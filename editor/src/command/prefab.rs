@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::{prefab, SelectedEntities};
+
+/// Saves the active entity and its descendants as a prefab
+pub fn create(world: &mut World) {
+    let Some(&root) = world.resource::<SelectedEntities>().0.last() else {
+        info!("No entity is selected.");
+        return;
+    };
+
+    let mut dialog = rfd::FileDialog::new().add_filter("prefab", &["prefab.ron"]);
+    if let Ok(path) = std::env::current_dir() {
+        dialog = dialog.set_directory(path);
+    }
+
+    let Some(path) = dialog.save_file() else {
+        error!("Failed to get file path.");
+        return;
+    };
+
+    prefab::create(world, root, &path);
+}
+
+pub fn place(world: &mut World) {
+    let mut dialog = rfd::FileDialog::new().add_filter("prefab", &["prefab.ron"]);
+    if let Ok(path) = std::env::current_dir() {
+        dialog = dialog.set_directory(path);
+    }
+
+    let Some(path) = dialog.pick_file() else {
+        error!("Failed to get file path.");
+        return;
+    };
+
+    if let Some(root) = prefab::instantiate(world, &path) {
+        let mut selected = world.resource_mut::<SelectedEntities>();
+        selected.0.clear();
+        selected.0.insert(root);
+    }
+}
+
+/// Saves the selected instances to their prefabs
+pub fn apply(world: &mut World) {
+    let selected = world.resource::<SelectedEntities>().0.clone();
+    for entity in selected {
+        prefab::apply(world, entity);
+    }
+}
+
+pub fn revert(world: &mut World) {
+    let selected = world.resource::<SelectedEntities>().0.clone();
+    for entity in selected {
+        prefab::revert(world, entity);
+    }
+}
@@ -1,12 +1,87 @@
-use std::{fs::File, io::Write, path::PathBuf};
-
-use bevy::{prelude::*, tasks::IoTaskPool};
-
-use crate::EditorEntity;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*, render::primitives::Aabb, scene::serde::SceneDeserializer, tasks::IoTaskPool,
+};
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+
+use crate::{
+    camera::{CameraBookmark, CameraBookmarks},
+    material::{restore_names, MaterialLibrary},
+    mesh_file::MeshFile,
+    primitive::Primitive,
+    EditorEntity,
+};
 
 #[derive(Resource, Deref, DerefMut)]
 pub struct SceneFilePath(pub PathBuf);
 
+/// Editor only data that's saved next to the scene instead of in it
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct EditorMetadata {
+    pub camera_bookmarks: Vec<CameraBookmark>,
+    /// Material names by asset path, for the materials loaded from files
+    pub material_names: BTreeMap<String, String>,
+}
+
+/// `level.scn.ron` -> `level.editor.ron`
+pub fn metadata_path(scene_path: &Path) -> PathBuf {
+    let file_name = scene_path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let stem = file_name.strip_suffix(".scn.ron").unwrap_or(&file_name);
+
+    scene_path.with_file_name(format!("{stem}.editor.ron"))
+}
+
+fn load_metadata(world: &mut World, scene_path: &Path) {
+    // Scenes without metadata don't keep the previous scene's
+    world.insert_resource(CameraBookmarks::default());
+    restore_names(world, BTreeMap::new());
+
+    let path = metadata_path(scene_path);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+
+    let metadata = match ron::from_str::<EditorMetadata>(&contents) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            error!("Failed to read editor metadata: {path:?}\n{e:?}");
+            return;
+        }
+    };
+
+    world.insert_resource(CameraBookmarks(metadata.camera_bookmarks));
+    restore_names(world, metadata.material_names);
+}
+
+/// Serializes `scene` as RON using the world's type registry
+pub fn serialize(world: &World, scene: &DynamicScene) -> Result<String, ron::Error> {
+    let type_registry = world.resource::<AppTypeRegistry>();
+    let type_registry = type_registry.read();
+    scene.serialize(&type_registry)
+}
+
+/// Deserializes a RON scene using the world's type registry
+pub fn deserialize(world: &World, contents: &str) -> Result<DynamicScene, ron::Error> {
+    let type_registry = world.resource::<AppTypeRegistry>();
+    let type_registry = type_registry.read();
+
+    let mut deserializer = ron::de::Deserializer::from_str(contents).map_err(|e| e.code)?;
+    SceneDeserializer {
+        type_registry: &type_registry,
+    }
+    .deserialize(&mut deserializer)
+}
+
 pub fn open(world: &mut World) {
     let mut dialog = rfd::FileDialog::new().add_filter("bevy scene", &["scn.ron"]);
     if let Ok(path) = std::env::current_dir() {
@@ -21,9 +96,44 @@ pub fn open(world: &mut World) {
     let scene = world.load_asset(path.clone());
 
     world.spawn(SceneBundle { scene, ..default() });
+    load_metadata(world, &path);
     world.insert_resource(SceneFilePath(path));
 }
 
+/// Whether the mesh of an entity is generated or loaded from its components
+/// when the scene is opened
+fn has_generated_mesh(entity: &EntityRef) -> bool {
+    entity.contains::<Primitive>() || entity.contains::<MeshFile>()
+}
+
+/// Builds the scene that is saved, from every entity that isn't part of the editor
+fn build_scene(world: &World) -> DynamicScene {
+    // Meshes are saved as the components that generate or load them, not as mesh assets
+    let scene_builder = DynamicSceneBuilder::from_world(world)
+        .allow_all()
+        // .deny::<Handle<StandardMaterial>>()
+        .deny_all_resources()
+        .allow_resource::<Assets<StandardMaterial>>()
+        .extract_resources();
+
+    let (generated, entities): (Vec<_>, Vec<_>) = world
+        .iter_entities()
+        .filter(|entity| {
+            !entity.contains::<EditorEntity>()
+                && !entity.contains::<Window>()
+                && !entity.contains::<bevy_mod_picking::prelude::PointerId>()
+        })
+        .partition(has_generated_mesh);
+
+    scene_builder
+        .extract_entities(entities.iter().map(EntityRef::id))
+        // Only the parameters or file are saved, the mesh and its bounds are made again
+        .deny::<Handle<Mesh>>()
+        .deny::<Aabb>()
+        .extract_entities(generated.iter().map(EntityRef::id))
+        .build()
+}
+
 pub fn save<const AS: bool>(world: &mut World) {
     if AS || !world.contains_resource::<SceneFilePath>() {
         let mut dialog = rfd::FileDialog::new().add_filter("bevy scene", &["scn.ron"]);
@@ -39,30 +149,17 @@ pub fn save<const AS: bool>(world: &mut World) {
         world.insert_resource(SceneFilePath(path));
     }
 
-    let mut scene_builder = DynamicSceneBuilder::from_world(world)
-        .allow_all()
-        // .deny::<Handle<Mesh>>()
-        // .deny::<Handle<StandardMaterial>>()
-        .deny_all_resources()
-        .allow_resource::<Assets<Mesh>>()
-        .allow_resource::<Assets<StandardMaterial>>()
-        .extract_resources();
-
-    for entity in world.iter_entities() {
-        if entity.contains::<EditorEntity>()
-            || entity.contains::<Window>()
-            || entity.contains::<bevy_mod_picking::prelude::PointerId>()
-        {
-            continue;
-        }
-
-        scene_builder = scene_builder.extract_entity(entity.id());
-    }
+    let scene = build_scene(world);
+    let serialized_scene = serialize(world, &scene).unwrap(); // FIXME: Dont Panic
 
-    let scene = scene_builder.build();
-    let type_registry = world.resource::<AppTypeRegistry>();
-    let type_registry = type_registry.read();
-    let serialized_scene = scene.serialize(&type_registry).unwrap(); // FIXME: Dont Panic
+    let metadata = EditorMetadata {
+        camera_bookmarks: world.resource::<CameraBookmarks>().0.clone(),
+        material_names: world
+            .resource::<MaterialLibrary>()
+            .loaded_names(world.resource::<AssetServer>()),
+    };
+    let serialized_metadata =
+        ron::ser::to_string_pretty(&metadata, ron::ser::PrettyConfig::default()).unwrap();
 
     let path = world.resource::<SceneFilePath>().0.clone();
 
@@ -73,6 +170,86 @@ pub fn save<const AS: bool>(world: &mut World) {
             {
                 error!("Failed to write scene to file: {path:?}\n{e:?}");
             }
+
+            let metadata_path = metadata_path(&path);
+            if let Err(e) = File::create(&metadata_path)
+                .and_then(|mut file| file.write(serialized_metadata.as_bytes()))
+            {
+                error!("Failed to write editor metadata to file: {metadata_path:?}\n{e:?}");
+            }
         })
         .detach();
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use bevy::reflect::GetTypeRegistration;
+
+    use super::*;
+
+    /// The types of the components of `entity` in the saved scene
+    fn saved_components(world: &World, entity: Entity) -> Vec<TypeId> {
+        let scene = build_scene(world);
+        let saved = scene
+            .entities
+            .iter()
+            .find(|saved| saved.entity == entity)
+            .expect("The entity isn't saved");
+        saved
+            .components
+            .iter()
+            .filter_map(|component| component.get_represented_type_info())
+            .map(|info| info.type_id())
+            .collect()
+    }
+
+    /// A world that can save `T`, mesh handles and bounds
+    fn world_with<T: GetTypeRegistration>() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<T>();
+            registry.register::<Handle<Mesh>>();
+            registry.register::<Aabb>();
+        }
+        world.insert_resource(registry);
+        world
+    }
+
+    #[test]
+    fn saved_primitive_only_has_its_parameters() {
+        let mut world = world_with::<Primitive>();
+        let entity = world
+            .spawn((
+                Primitive::default(),
+                Handle::<Mesh>::default(),
+                Aabb::default(),
+            ))
+            .id();
+
+        assert_eq!(
+            saved_components(&world, entity),
+            [TypeId::of::<Primitive>()]
+        );
+    }
+
+    #[test]
+    fn saved_mesh_file_only_has_its_path() {
+        let mut world = world_with::<MeshFile>();
+        let entity = world
+            .spawn((
+                MeshFile {
+                    path: "models/cube.obj".to_string(),
+                    part: 0,
+                },
+                Handle::<Mesh>::default(),
+                Aabb::default(),
+            ))
+            .id();
+
+        assert_eq!(saved_components(&world, entity), [TypeId::of::<MeshFile>()]);
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::{
+        ActiveCamera, AlignView, BookmarkKey, CameraMode, Flycam, FrameEntities,
+        LookThroughSelected, RecallBookmark, StoreBookmark, ToggleOrthographic, TogglePilot,
+        ViewDirection,
+    },
+    picking::Cursor3d,
+    SelectedEntities,
+};
+
+/// Switches the active camera between [`CameraMode::Fly`] and [`CameraMode::Orbit`]
+pub fn toggle_camera_mode(mut query: Query<&mut Flycam, With<ActiveCamera>>) {
+    for mut flycam in query.iter_mut() {
+        flycam.mode = match flycam.mode {
+            CameraMode::Fly => CameraMode::Orbit,
+            CameraMode::Orbit => CameraMode::Fly,
+        };
+    }
+}
+
+pub fn frame_selected(mut commands: Commands) {
+    commands.trigger(FrameEntities::Selected);
+}
+
+pub fn frame_all(mut commands: Commands) {
+    commands.trigger(FrameEntities::All);
+}
+
+pub fn align_view(direction: ViewDirection) -> impl FnMut(Commands) + Send + Sync + 'static {
+    move |mut commands: Commands| {
+        commands.trigger(AlignView(direction));
+    }
+}
+
+pub fn toggle_orthographic(mut commands: Commands) {
+    commands.trigger(ToggleOrthographic);
+}
+
+pub fn add_bookmark(mut commands: Commands) {
+    commands.trigger(StoreBookmark(None));
+}
+
+/// Recalls the bookmark in `slot`, like Ctrl + `slot`
+pub fn recall_bookmark(slot: u8) -> impl FnMut(Commands) + Send + Sync + 'static {
+    move |mut commands: Commands| {
+        commands.trigger(RecallBookmark(BookmarkKey::Slot(slot)));
+    }
+}
+
+pub fn look_through_selected(mut commands: Commands) {
+    commands.trigger(LookThroughSelected);
+}
+
+pub fn toggle_pilot(mut commands: Commands) {
+    commands.trigger(TogglePilot);
+}
+
+/// Places new entities in front of the camera again
+pub fn clear_cursor(mut cursor: ResMut<Cursor3d>) {
+    cursor.position = None;
+}
+
+/// Moves the 3D cursor to the center of the selected entities
+pub fn cursor_to_selected(
+    selected: Res<SelectedEntities>,
+    query: Query<&GlobalTransform>,
+    mut cursor: ResMut<Cursor3d>,
+) {
+    let positions = selected
+        .0
+        .iter()
+        .filter_map(|&entity| query.get(entity).ok())
+        .map(|global_transform| global_transform.translation())
+        .collect::<Vec<Vec3>>();
+
+    if positions.is_empty() {
+        info!("No entity is selected.");
+        return;
+    }
+
+    cursor.position = Some(positions.iter().sum::<Vec3>() / positions.len() as f32);
+}
@@ -0,0 +1,699 @@
+use std::{borrow::Cow, collections::BTreeMap, f32::consts::PI, io::Cursor};
+
+use bevy::{
+    pbr::UvChannel,
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+        render_resource::TextureFormat,
+        texture::{ImageAddressMode, ImageFilterMode, ImageSampler, ImageSamplerDescriptor},
+    },
+    utils::HashMap,
+};
+use gltf::{
+    binary::{Glb, Header},
+    json::{
+        self,
+        accessor::{ComponentType, GenericComponentType, Type},
+        buffer::Target,
+        extensions::{
+            material::{EmissiveStrength, EmissiveStrengthFactor, Unlit},
+            scene::khr_lights_punctual::{self, KhrLightsPunctual},
+        },
+        material::{AlphaCutoff, EmissiveFactor, PbrBaseColorFactor, StrengthFactor},
+        mesh::{Mode, Semantic},
+        texture::{MagFilter, MinFilter, WrappingMode},
+        validation::{Checked, USize64},
+        Index,
+    },
+};
+
+use crate::EditorEntity;
+
+/// Builds a glTF document from entities of the world
+struct Exporter<'w> {
+    world: &'w World,
+    root: json::Root,
+    /// The binary chunk, every buffer view points into it
+    buffer: Vec<u8>,
+    lights: Vec<khr_lights_punctual::Light>,
+    /// glTF meshes include their material, so they're shared per mesh and material pair
+    meshes: HashMap<(AssetId<Mesh>, Option<AssetId<StandardMaterial>>), Option<Index<json::Mesh>>>,
+    materials: HashMap<AssetId<StandardMaterial>, Option<Index<json::Material>>>,
+    textures: HashMap<AssetId<Image>, Option<Index<json::Texture>>>,
+}
+
+impl Exporter<'_> {
+    fn use_extension(&mut self, name: &str) {
+        if !self.root.extensions_used.iter().any(|used| used == name) {
+            self.root.extensions_used.push(name.to_string());
+        }
+    }
+
+    /// Appends `bytes` to the binary chunk
+    fn view(&mut self, bytes: &[u8], target: Option<Target>) -> Index<json::buffer::View> {
+        // Accessors need their data aligned to their component size, 4 fits every type
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+
+        let view = json::buffer::View {
+            buffer: Index::new(0),
+            byte_length: USize64::from(bytes.len()),
+            byte_offset: Some(USize64::from(self.buffer.len())),
+            byte_stride: None,
+            name: None,
+            target: target.map(Checked::Valid),
+            extensions: None,
+            extras: default(),
+        };
+        self.buffer.extend_from_slice(bytes);
+        self.root.push(view)
+    }
+
+    fn float_accessor<const N: usize>(&mut self, values: &[[f32; N]]) -> Index<json::Accessor> {
+        let bytes = values
+            .iter()
+            .flatten()
+            .flat_map(|value| value.to_le_bytes())
+            .collect::<Vec<u8>>();
+        let view = self.view(&bytes, Some(Target::ArrayBuffer));
+
+        // Only required for positions, but cheap to write for every attribute
+        let mut min = [f32::MAX; N];
+        let mut max = [f32::MIN; N];
+        for value in values {
+            for ((min, max), value) in min.iter_mut().zip(max.iter_mut()).zip(value) {
+                *min = min.min(*value);
+                *max = max.max(*value);
+            }
+        }
+
+        self.root.push(json::Accessor {
+            buffer_view: Some(view),
+            byte_offset: None,
+            count: USize64::from(values.len()),
+            component_type: Checked::Valid(GenericComponentType(ComponentType::F32)),
+            extensions: None,
+            extras: default(),
+            type_: Checked::Valid(match N {
+                2 => Type::Vec2,
+                3 => Type::Vec3,
+                _ => Type::Vec4,
+            }),
+            min: Some(json::Value::from(min.to_vec())),
+            max: Some(json::Value::from(max.to_vec())),
+            name: None,
+            normalized: false,
+            sparse: None,
+        })
+    }
+
+    fn index_accessor(&mut self, indices: &Indices) -> Index<json::Accessor> {
+        let (bytes, component_type) = match indices {
+            Indices::U16(indices) => (
+                indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes())
+                    .collect(),
+                ComponentType::U16,
+            ),
+            Indices::U32(indices) => (
+                indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes())
+                    .collect(),
+                ComponentType::U32,
+            ),
+        };
+        let view = self.view(&bytes, Some(Target::ElementArrayBuffer));
+
+        self.root.push(json::Accessor {
+            buffer_view: Some(view),
+            byte_offset: None,
+            count: USize64::from(indices.len()),
+            component_type: Checked::Valid(GenericComponentType(component_type)),
+            extensions: None,
+            extras: default(),
+            type_: Checked::Valid(Type::Scalar),
+            min: None,
+            max: None,
+            name: None,
+            normalized: false,
+            sparse: None,
+        })
+    }
+
+    fn mesh(
+        &mut self,
+        mesh: &Handle<Mesh>,
+        material: Option<&Handle<StandardMaterial>>,
+    ) -> Option<Index<json::Mesh>> {
+        let key = (mesh.id(), material.map(Handle::id));
+        if let Some(index) = self.meshes.get(&key) {
+            return *index;
+        }
+
+        let index = self.export_mesh(mesh, material);
+        self.meshes.insert(key, index);
+        index
+    }
+
+    fn export_mesh(
+        &mut self,
+        handle: &Handle<Mesh>,
+        material: Option<&Handle<StandardMaterial>>,
+    ) -> Option<Index<json::Mesh>> {
+        let world = self.world;
+        let mesh = world.resource::<Assets<Mesh>>().get(handle)?;
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            warn!("Skipped a mesh without positions: {:?}", handle.path());
+            return None;
+        };
+        // glTF accessors can't be empty
+        if positions.is_empty() {
+            return None;
+        }
+
+        let mode = match mesh.primitive_topology() {
+            PrimitiveTopology::PointList => Mode::Points,
+            PrimitiveTopology::LineList => Mode::Lines,
+            PrimitiveTopology::LineStrip => Mode::LineStrip,
+            PrimitiveTopology::TriangleList => Mode::Triangles,
+            PrimitiveTopology::TriangleStrip => Mode::TriangleStrip,
+        };
+
+        let mut attributes = BTreeMap::new();
+        attributes.insert(
+            Checked::Valid(Semantic::Positions),
+            self.float_accessor(positions),
+        );
+        if let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        {
+            attributes.insert(
+                Checked::Valid(Semantic::Normals),
+                self.float_accessor(normals),
+            );
+        }
+        if let Some(VertexAttributeValues::Float32x4(tangents)) =
+            mesh.attribute(Mesh::ATTRIBUTE_TANGENT)
+        {
+            attributes.insert(
+                Checked::Valid(Semantic::Tangents),
+                self.float_accessor(tangents),
+            );
+        }
+        for (set, attribute) in [Mesh::ATTRIBUTE_UV_0, Mesh::ATTRIBUTE_UV_1]
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(attribute) {
+                attributes.insert(
+                    Checked::Valid(Semantic::TexCoords(set as u32)),
+                    self.float_accessor(uvs),
+                );
+            }
+        }
+        // Both are linear
+        if let Some(VertexAttributeValues::Float32x4(colors)) =
+            mesh.attribute(Mesh::ATTRIBUTE_COLOR)
+        {
+            attributes.insert(
+                Checked::Valid(Semantic::Colors(0)),
+                self.float_accessor(colors),
+            );
+        }
+
+        let indices = mesh.indices().map(|indices| self.index_accessor(indices));
+        let material = material.and_then(|material| self.material(material));
+
+        Some(self.root.push(json::Mesh {
+            extensions: None,
+            extras: default(),
+            name: None,
+            primitives: vec![json::mesh::Primitive {
+                attributes,
+                extensions: None,
+                extras: default(),
+                indices,
+                material,
+                mode: Checked::Valid(mode),
+                targets: None,
+            }],
+            weights: None,
+        }))
+    }
+
+    fn material(&mut self, handle: &Handle<StandardMaterial>) -> Option<Index<json::Material>> {
+        if let Some(index) = self.materials.get(&handle.id()) {
+            return *index;
+        }
+
+        let index = self.export_material(handle);
+        self.materials.insert(handle.id(), index);
+        index
+    }
+
+    fn export_material(
+        &mut self,
+        handle: &Handle<StandardMaterial>,
+    ) -> Option<Index<json::Material>> {
+        let world = self.world;
+        let material = world.resource::<Assets<StandardMaterial>>().get(handle)?;
+
+        let (alpha_mode, alpha_cutoff) = match material.alpha_mode {
+            AlphaMode::Opaque => (json::material::AlphaMode::Opaque, None),
+            AlphaMode::Mask(cutoff) => (json::material::AlphaMode::Mask, Some(AlphaCutoff(cutoff))),
+            // glTF has no other blend modes, blending is the closest
+            _ => (json::material::AlphaMode::Blend, None),
+        };
+
+        // glTF emissive factors go up to 1, brighter ones are scaled by the emissive strength
+        let emissive = material.emissive;
+        let strength = [emissive.red, emissive.green, emissive.blue]
+            .into_iter()
+            .fold(1.0, f32::max);
+
+        let mut extensions = json::extensions::material::Material::default();
+        if material.unlit {
+            extensions.unlit = Some(Unlit {});
+            self.use_extension("KHR_materials_unlit");
+        }
+        if strength > 1.0 {
+            extensions.emissive_strength = Some(EmissiveStrength {
+                emissive_strength: EmissiveStrengthFactor(strength),
+            });
+            self.use_extension("KHR_materials_emissive_strength");
+        }
+
+        let normal_texture = self
+            .texture_info(
+                material.normal_map_texture.as_ref(),
+                &material.normal_map_channel,
+            )
+            .map(|info| json::material::NormalTexture {
+                index: info.index,
+                scale: 1.0,
+                tex_coord: info.tex_coord,
+                extensions: None,
+                extras: default(),
+            });
+        let occlusion_texture = self
+            .texture_info(
+                material.occlusion_texture.as_ref(),
+                &material.occlusion_channel,
+            )
+            .map(|info| json::material::OcclusionTexture {
+                index: info.index,
+                strength: StrengthFactor(1.0),
+                tex_coord: info.tex_coord,
+                extensions: None,
+                extras: default(),
+            });
+
+        let pbr_metallic_roughness = json::material::PbrMetallicRoughness {
+            base_color_factor: PbrBaseColorFactor(
+                LinearRgba::from(material.base_color).to_f32_array(),
+            ),
+            base_color_texture: self.texture_info(
+                material.base_color_texture.as_ref(),
+                &material.base_color_channel,
+            ),
+            metallic_factor: StrengthFactor(material.metallic),
+            roughness_factor: StrengthFactor(material.perceptual_roughness),
+            metallic_roughness_texture: self.texture_info(
+                material.metallic_roughness_texture.as_ref(),
+                &material.metallic_roughness_channel,
+            ),
+            extensions: None,
+            extras: default(),
+        };
+
+        let material = json::Material {
+            alpha_cutoff,
+            alpha_mode: Checked::Valid(alpha_mode),
+            double_sided: material.cull_mode.is_none(),
+            name: None,
+            pbr_metallic_roughness,
+            normal_texture,
+            occlusion_texture,
+            emissive_texture: self.texture_info(
+                material.emissive_texture.as_ref(),
+                &material.emissive_channel,
+            ),
+            emissive_factor: EmissiveFactor([
+                emissive.red / strength,
+                emissive.green / strength,
+                emissive.blue / strength,
+            ]),
+            extensions: (extensions.unlit.is_some() || extensions.emissive_strength.is_some())
+                .then_some(extensions),
+            extras: default(),
+        };
+        Some(self.root.push(material))
+    }
+
+    fn texture_info(
+        &mut self,
+        handle: Option<&Handle<Image>>,
+        channel: &UvChannel,
+    ) -> Option<json::texture::Info> {
+        Some(json::texture::Info {
+            index: self.texture(handle?)?,
+            tex_coord: match channel {
+                UvChannel::Uv0 => 0,
+                UvChannel::Uv1 => 1,
+            },
+            extensions: None,
+            extras: default(),
+        })
+    }
+
+    fn texture(&mut self, handle: &Handle<Image>) -> Option<Index<json::Texture>> {
+        if let Some(index) = self.textures.get(&handle.id()) {
+            return *index;
+        }
+
+        let index = self.export_texture(handle);
+        self.textures.insert(handle.id(), index);
+        index
+    }
+
+    fn export_texture(&mut self, handle: &Handle<Image>) -> Option<Index<json::Texture>> {
+        let world = self.world;
+        let texture = world.resource::<Assets<Image>>().get(handle)?;
+
+        let png = match encode_png(texture) {
+            Ok(png) => png,
+            Err(e) => {
+                warn!("Skipped a texture: {:?}\n{e}", handle.path());
+                return None;
+            }
+        };
+
+        let view = self.view(&png, None);
+        let source = self.root.push(json::Image {
+            buffer_view: Some(view),
+            mime_type: Some(json::image::MimeType("image/png".to_string())),
+            name: handle.path().map(|path| path.to_string()),
+            uri: None,
+            extensions: None,
+            extras: default(),
+        });
+        let sampler = self.root.push(sampler(&texture.sampler));
+
+        Some(self.root.push(json::Texture {
+            name: None,
+            sampler: Some(sampler),
+            source,
+            extensions: None,
+            extras: default(),
+        }))
+    }
+
+    fn light(&mut self, entity: EntityRef) -> Option<Index<khr_lights_punctual::Light>> {
+        // glTF point and spot lights are in candela, the reverse of Bevy's glTF loader
+        let (type_, color, intensity, range, spot) = if let Some(light) = entity.get::<PointLight>()
+        {
+            let intensity = light.intensity / (4.0 * PI);
+            (
+                khr_lights_punctual::Type::Point,
+                light.color,
+                intensity,
+                Some(light.range),
+                None,
+            )
+        } else if let Some(light) = entity.get::<SpotLight>() {
+            let spot = khr_lights_punctual::Spot {
+                inner_cone_angle: light.inner_angle,
+                outer_cone_angle: light.outer_angle,
+            };
+            let intensity = light.intensity / (4.0 * PI);
+            (
+                khr_lights_punctual::Type::Spot,
+                light.color,
+                intensity,
+                Some(light.range),
+                Some(spot),
+            )
+        } else if let Some(light) = entity.get::<DirectionalLight>() {
+            // Both are in lux
+            (
+                khr_lights_punctual::Type::Directional,
+                light.color,
+                light.illuminance,
+                None,
+                None,
+            )
+        } else {
+            return None;
+        };
+
+        let color = LinearRgba::from(color);
+        Some(Index::push(
+            &mut self.lights,
+            khr_lights_punctual::Light {
+                color: [color.red, color.green, color.blue],
+                extensions: None,
+                extras: default(),
+                intensity,
+                name: entity.get::<Name>().map(|name| name.to_string()),
+                range,
+                spot,
+                type_: Checked::Valid(type_),
+            },
+        ))
+    }
+
+    fn camera(&mut self, projection: &Projection, name: Option<String>) -> Index<json::Camera> {
+        let camera = match projection {
+            Projection::Perspective(projection) => json::Camera {
+                name,
+                orthographic: None,
+                perspective: Some(json::camera::Perspective {
+                    aspect_ratio: Some(projection.aspect_ratio),
+                    yfov: projection.fov,
+                    zfar: Some(projection.far),
+                    znear: projection.near,
+                    extensions: None,
+                    extras: default(),
+                }),
+                type_: Checked::Valid(json::camera::Type::Perspective),
+                extensions: None,
+                extras: default(),
+            },
+            Projection::Orthographic(projection) => json::Camera {
+                name,
+                orthographic: Some(json::camera::Orthographic {
+                    xmag: projection.area.half_size().x,
+                    ymag: projection.area.half_size().y,
+                    zfar: projection.far,
+                    // Bevy allows a negative near plane, glTF doesn't
+                    znear: projection.near.max(0.0),
+                    extensions: None,
+                    extras: default(),
+                }),
+                perspective: None,
+                type_: Checked::Valid(json::camera::Type::Orthographic),
+                extensions: None,
+                extras: default(),
+            },
+        };
+        self.root.push(camera)
+    }
+
+    /// Exports `entity` and its descendants, editor entities are skipped
+    fn node(&mut self, entity: Entity, transform: Transform) -> Option<Index<json::Node>> {
+        let world = self.world;
+        let entity = world.get_entity(entity)?;
+        if entity.contains::<EditorEntity>() {
+            return None;
+        }
+
+        let children = entity
+            .get::<Children>()
+            .into_iter()
+            .flatten()
+            .filter_map(|&child| {
+                let transform = world.get::<Transform>(child).copied().unwrap_or_default();
+                self.node(child, transform)
+            })
+            .collect::<Vec<_>>();
+
+        let name = entity.get::<Name>().map(|name| name.to_string());
+        let mesh = entity
+            .get::<Handle<Mesh>>()
+            .and_then(|mesh| self.mesh(mesh, entity.get::<Handle<StandardMaterial>>()));
+        let camera = entity
+            .get::<Projection>()
+            .filter(|_| entity.contains::<Camera>())
+            .map(|projection| self.camera(projection, name.clone()));
+        // Bevy's lights and cameras look down -Z like glTF's, so they need no extra node
+        let light = self.light(entity);
+
+        let node = json::Node {
+            camera,
+            children: (!children.is_empty()).then_some(children),
+            extensions: light.map(|light| json::extensions::scene::Node {
+                khr_lights_punctual: Some(KhrLightsPunctual { light }),
+                ..default()
+            }),
+            mesh,
+            name,
+            rotation: Some(json::scene::UnitQuaternion(transform.rotation.to_array())),
+            scale: Some(transform.scale.to_array()),
+            translation: Some(transform.translation.to_array()),
+            ..default()
+        };
+        Some(self.root.push(node))
+    }
+
+    fn finish(mut self, nodes: Vec<Index<json::Node>>) -> Result<Glb<'static>, json::Error> {
+        let scene = self.root.push(json::Scene {
+            extensions: None,
+            extras: default(),
+            name: None,
+            nodes,
+        });
+        self.root.scene = Some(scene);
+
+        if !self.lights.is_empty() {
+            self.use_extension("KHR_lights_punctual");
+            self.root.extensions = Some(json::extensions::Root {
+                khr_lights_punctual: Some(json::extensions::root::KhrLightsPunctual {
+                    lights: self.lights,
+                }),
+                ..default()
+            });
+        }
+
+        // Both chunks are padded to 4 bytes, JSON with spaces
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+        if !self.buffer.is_empty() {
+            self.root.push(json::Buffer {
+                byte_length: USize64::from(self.buffer.len()),
+                name: None,
+                uri: None,
+                extensions: None,
+                extras: default(),
+            });
+        }
+
+        let mut json = json::serialize::to_vec(&self.root)?;
+        json.resize(json.len().next_multiple_of(4), b' ');
+
+        // The header, then the length and type of each chunk before it
+        let mut length = 12 + 8 + json.len();
+        if !self.buffer.is_empty() {
+            length += 8 + self.buffer.len();
+        }
+
+        Ok(Glb {
+            header: Header {
+                magic: *b"glTF",
+                version: 2,
+                length: length as u32,
+            },
+            json: Cow::Owned(json),
+            bin: (!self.buffer.is_empty()).then_some(Cow::Owned(self.buffer)),
+        })
+    }
+}
+
+/// Encodes 8 bit textures as PNG, the data of normal maps is written as is
+fn encode_png(texture: &Image) -> Result<Vec<u8>, String> {
+    if texture.texture_descriptor.size.depth_or_array_layers > 1 {
+        return Err("Array textures and cubemaps aren't supported".to_string());
+    }
+    if texture.data.is_empty() {
+        return Err("The texture has no data in the main world".to_string());
+    }
+
+    let size = texture.size();
+    let dynamic = match texture.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            image::RgbaImage::from_raw(size.x, size.y, texture.data.clone())
+                .map(image::DynamicImage::ImageRgba8)
+                .ok_or_else(|| "The texture data is shorter than its size".to_string())?
+        }
+        _ => texture
+            .clone()
+            .try_into_dynamic()
+            .map_err(|e| e.to_string())?,
+    };
+
+    let mut png = Cursor::new(Vec::new());
+    dynamic
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(png.into_inner())
+}
+
+fn sampler(sampler: &ImageSampler) -> json::texture::Sampler {
+    // What `ImagePlugin::default` uses
+    let descriptor = match sampler {
+        ImageSampler::Default => ImageSamplerDescriptor::linear(),
+        ImageSampler::Descriptor(descriptor) => descriptor.clone(),
+    };
+
+    let wrap = |mode: ImageAddressMode| {
+        Checked::Valid(match mode {
+            ImageAddressMode::Repeat => WrappingMode::Repeat,
+            ImageAddressMode::MirrorRepeat => WrappingMode::MirroredRepeat,
+            ImageAddressMode::ClampToEdge | ImageAddressMode::ClampToBorder => {
+                WrappingMode::ClampToEdge
+            }
+        })
+    };
+
+    json::texture::Sampler {
+        mag_filter: Some(Checked::Valid(match descriptor.mag_filter {
+            ImageFilterMode::Nearest => MagFilter::Nearest,
+            ImageFilterMode::Linear => MagFilter::Linear,
+        })),
+        min_filter: Some(Checked::Valid(match descriptor.min_filter {
+            ImageFilterMode::Nearest => MinFilter::Nearest,
+            ImageFilterMode::Linear => MinFilter::Linear,
+        })),
+        name: None,
+        wrap_s: wrap(descriptor.address_mode_u),
+        wrap_t: wrap(descriptor.address_mode_v),
+        extensions: None,
+        extras: default(),
+    }
+}
+
+/// Exports `roots` and their descendants as binary glTF.
+/// Roots are placed where they are in the world, since their parents aren't exported
+pub fn export(world: &World, roots: &[Entity]) -> Result<Glb<'static>, json::Error> {
+    let mut exporter = Exporter {
+        world,
+        root: json::Root {
+            asset: json::Asset {
+                generator: Some("Viro".to_string()),
+                ..default()
+            },
+            ..default()
+        },
+        buffer: Vec::new(),
+        lights: Vec::new(),
+        meshes: HashMap::new(),
+        materials: HashMap::new(),
+        textures: HashMap::new(),
+    };
+
+    let nodes = roots
+        .iter()
+        .filter_map(|&root| {
+            let transform = world
+                .get::<GlobalTransform>(root)
+                .map(GlobalTransform::compute_transform)
+                .unwrap_or_default();
+            exporter.node(root, transform)
+        })
+        .collect();
+
+    exporter.finish(nodes)
+}
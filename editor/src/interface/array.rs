@@ -0,0 +1,351 @@
+use bevy::{color::palettes::css::SKY_BLUE, prelude::*, render::primitives::Aabb};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{
+    observers::{clone_entity, entity_names, rename_unique},
+    SelectedEntities, UndoRecord, UndoStack,
+};
+
+use super::InterfaceSet;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ArrayPattern {
+    /// Copies moved by `offset` from the previous one
+    Linear { offset: Vec3 },
+    /// Copies rotated around `pivot`, spread over `angle` degrees
+    Radial { pivot: Vec3, axis: Vec3, angle: f32 },
+    /// Copies in a grid of `counts` cells, the original is the first cell
+    Grid { counts: UVec3, spacing: Vec3 },
+}
+
+/// The parameters of the array popup, the popup is shown while this exists
+#[derive(Resource, Clone)]
+pub struct ArrayTool {
+    pub pattern: ArrayPattern,
+    /// The number of copies of linear and radial arrays
+    pub count: u32,
+}
+
+impl Default for ArrayTool {
+    fn default() -> Self {
+        Self {
+            pattern: ArrayPattern::Linear { offset: Vec3::X },
+            count: 3,
+        }
+    }
+}
+
+impl ArrayTool {
+    /// The world space transforms that move the selection to each copy
+    pub fn offsets(&self) -> Vec<Transform> {
+        match self.pattern {
+            ArrayPattern::Linear { offset } => (1..=self.count)
+                .map(|i| Transform::from_translation(offset * i as f32))
+                .collect(),
+            ArrayPattern::Radial { pivot, axis, angle } => {
+                // A full circle doesn't put the last copy on top of the original
+                let step = if angle.abs() >= 360.0 {
+                    angle / (self.count + 1) as f32
+                } else {
+                    angle / self.count.max(1) as f32
+                };
+
+                (1..=self.count)
+                    .map(|i| {
+                        let rotation = Quat::from_axis_angle(
+                            axis.normalize_or(Vec3::Y),
+                            (step * i as f32).to_radians(),
+                        );
+                        let mut transform = Transform::from_rotation(rotation);
+                        transform.translation = pivot - rotation * pivot;
+                        transform
+                    })
+                    .collect()
+            }
+            ArrayPattern::Grid { counts, spacing } => {
+                let mut offsets = Vec::new();
+                for x in 0..counts.x {
+                    for y in 0..counts.y {
+                        for z in 0..counts.z {
+                            if x == 0 && y == 0 && z == 0 {
+                                continue;
+                            }
+
+                            let cell = Vec3::new(x as f32, y as f32, z as f32);
+                            offsets.push(Transform::from_translation(cell * spacing));
+                        }
+                    }
+                }
+                offsets
+            }
+        }
+    }
+}
+
+/// The selected entities without their selected descendants
+fn selected_roots(world: &World) -> Vec<Entity> {
+    let selected = &world.resource::<SelectedEntities>().0;
+    selected
+        .iter()
+        .filter(|&&entity| {
+            let mut current = entity;
+            while let Some(parent) = world.get::<Parent>(current) {
+                current = parent.get();
+                if selected.contains(&current) {
+                    return false;
+                }
+            }
+            true
+        })
+        .cloned()
+        .collect()
+}
+
+/// Creates the copies and selects them, undoing removes them
+fn apply(world: &mut World, tool: ArrayTool) {
+    let roots = selected_roots(world);
+    let previous_selection = world.resource::<SelectedEntities>().0.clone();
+    let mut names = entity_names(world);
+
+    let mut copies = Vec::new();
+    for offset in tool.offsets() {
+        for &entity in roots.iter() {
+            let Some(&global_transform) = world.get::<GlobalTransform>(entity) else {
+                continue;
+            };
+            let parent = world.get::<Parent>(entity).map(|parent| parent.get());
+            let parent_transform = parent
+                .and_then(|parent| world.get::<GlobalTransform>(parent))
+                .copied()
+                .unwrap_or_default();
+
+            let copy = clone_entity(world, entity);
+            if let Some(parent) = parent {
+                world.entity_mut(parent).add_child(copy);
+            }
+
+            let global_transform = GlobalTransform::from(offset) * global_transform;
+            world
+                .entity_mut(copy)
+                .insert(global_transform.reparented_to(&parent_transform));
+
+            rename_unique(world, copy, &mut names);
+            copies.push(copy);
+        }
+    }
+
+    world.resource_mut::<SelectedEntities>().0 = copies.iter().copied().collect();
+    world.resource_mut::<UndoStack>().0.push(UndoRecord {
+        name: "Array".to_string(),
+        undo: Box::new(move |world: &mut World| {
+            for copy in copies {
+                if let Some(entity_mut) = world.get_entity_mut(copy) {
+                    entity_mut.despawn_recursive();
+                }
+            }
+
+            let selection = previous_selection
+                .into_iter()
+                .filter(|&entity| world.get_entity(entity).is_some())
+                .collect();
+            world.resource_mut::<SelectedEntities>().0 = selection;
+        }),
+    });
+}
+
+fn show(
+    mut tool: ResMut<ArrayTool>,
+    selected: Res<SelectedEntities>,
+    query: Query<&GlobalTransform>,
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+) {
+    let ctx = contexts.ctx_mut();
+
+    let mut open = true;
+    let mut apply_clicked = false;
+    let mut cancel_clicked = false;
+    egui::Window::new("Array")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let pattern = &mut tool.pattern;
+            ui.horizontal(|ui| {
+                let is_linear = matches!(pattern, ArrayPattern::Linear { .. });
+                if ui.selectable_label(is_linear, "Linear").clicked() && !is_linear {
+                    *pattern = ArrayPattern::Linear { offset: Vec3::X };
+                }
+
+                let is_radial = matches!(pattern, ArrayPattern::Radial { .. });
+                if ui.selectable_label(is_radial, "Radial").clicked() && !is_radial {
+                    *pattern = ArrayPattern::Radial {
+                        pivot: Vec3::ZERO,
+                        axis: Vec3::Y,
+                        angle: 360.0,
+                    };
+                }
+
+                let is_grid = matches!(pattern, ArrayPattern::Grid { .. });
+                if ui.selectable_label(is_grid, "Grid").clicked() && !is_grid {
+                    *pattern = ArrayPattern::Grid {
+                        counts: UVec3::new(3, 1, 3),
+                        spacing: Vec3::splat(2.0),
+                    };
+                }
+            });
+
+            ui.separator();
+
+            let is_grid = matches!(tool.pattern, ArrayPattern::Grid { .. });
+            egui::Grid::new("Array Parameters")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    if !is_grid {
+                        ui.label("Copies");
+                        ui.add(egui::DragValue::new(&mut tool.count).range(1..=1000));
+                        ui.end_row();
+                    }
+
+                    match &mut tool.pattern {
+                        ArrayPattern::Linear { offset } => {
+                            ui.label("Offset");
+                            vec3_ui(ui, offset);
+                            ui.end_row();
+                        }
+                        ArrayPattern::Radial { pivot, axis, angle } => {
+                            ui.label("Pivot");
+                            ui.horizontal(|ui| {
+                                vec3_ui(ui, pivot);
+
+                                // Center of the selection
+                                if ui.button("Selection").clicked() {
+                                    let positions = selected
+                                        .0
+                                        .iter()
+                                        .filter_map(|&entity| query.get(entity).ok())
+                                        .map(|transform| transform.translation())
+                                        .collect::<Vec<Vec3>>();
+
+                                    if !positions.is_empty() {
+                                        *pivot =
+                                            positions.iter().sum::<Vec3>() / positions.len() as f32;
+                                    }
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label("Axis");
+                            ui.horizontal(|ui| {
+                                ui.selectable_value(axis, Vec3::X, "X");
+                                ui.selectable_value(axis, Vec3::Y, "Y");
+                                ui.selectable_value(axis, Vec3::Z, "Z");
+                            });
+                            ui.end_row();
+
+                            ui.label("Angle");
+                            ui.add(
+                                egui::DragValue::new(angle)
+                                    .range(-360.0..=360.0)
+                                    .suffix("°"),
+                            );
+                            ui.end_row();
+                        }
+                        ArrayPattern::Grid { counts, spacing } => {
+                            ui.label("Counts");
+                            ui.horizontal(|ui| {
+                                ui.add(egui::DragValue::new(&mut counts.x).range(1..=100));
+                                ui.add(egui::DragValue::new(&mut counts.y).range(1..=100));
+                                ui.add(egui::DragValue::new(&mut counts.z).range(1..=100));
+                            });
+                            ui.end_row();
+
+                            ui.label("Spacing");
+                            vec3_ui(ui, spacing);
+                            ui.end_row();
+                        }
+                    }
+                });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                apply_clicked = ui.button("Apply").clicked();
+                cancel_clicked = ui.button("Cancel").clicked();
+            });
+        });
+
+    if apply_clicked {
+        let tool = tool.clone();
+        commands.add(move |world: &mut World| apply(world, tool));
+    }
+
+    if !open || apply_clicked || cancel_clicked {
+        commands.remove_resource::<ArrayTool>();
+    }
+}
+
+fn vec3_ui(ui: &mut egui::Ui, value: &mut Vec3) {
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut value.x).speed(0.1).prefix("x: "));
+        ui.add(egui::DragValue::new(&mut value.y).speed(0.1).prefix("y: "));
+        ui.add(egui::DragValue::new(&mut value.z).speed(0.1).prefix("z: "));
+    });
+}
+
+/// Draws the bounds of every copy
+fn preview(
+    tool: Res<ArrayTool>,
+    selected: Res<SelectedEntities>,
+    query: Query<(&GlobalTransform, Option<&Aabb>)>,
+    children_query: Query<&Children>,
+    mut gizmos: Gizmos,
+) {
+    let offsets = tool.offsets();
+
+    for &root in selected.0.iter() {
+        for entity in std::iter::once(root).chain(children_query.iter_descendants(root)) {
+            let Ok((global_transform, aabb)) = query.get(entity) else {
+                continue;
+            };
+
+            for offset in offsets.iter() {
+                let transform = GlobalTransform::from(*offset) * *global_transform;
+
+                match aabb {
+                    Some(aabb) => {
+                        let (scale, rotation, _translation) =
+                            transform.to_scale_rotation_translation();
+
+                        gizmos.cuboid(
+                            Transform::from_translation(
+                                transform.transform_point(aabb.center.into()),
+                            )
+                            .with_rotation(rotation)
+                            .with_scale(Vec3::from(aabb.half_extents * 2.0) * scale),
+                            SKY_BLUE,
+                        );
+                    }
+                    None if entity == root => {
+                        gizmos.sphere(transform.translation(), Quat::IDENTITY, 0.1, SKY_BLUE);
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+pub struct ArrayToolPlugin;
+impl Plugin for ArrayToolPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (
+                show.in_set(InterfaceSet::Overlay),
+                preview.after(TransformSystem::TransformPropagate),
+            )
+                .run_if(resource_exists::<ArrayTool>),
+        );
+    }
+}
@@ -1,6 +1,18 @@
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    math::Affine2,
+    pbr::ParallaxMappingMethod,
+    prelude::*,
+    render::{render_resource::Face, texture::ImageLoaderSettings},
+    utils::HashMap,
+};
 use bevy_egui::egui::{self, ComboBox, DragValue};
-use std::any::TypeId;
+use std::{any::TypeId, ops::RangeInclusive};
+
+use crate::{
+    material::{duplicate_material, material_users, MaterialLibrary},
+    primitive::Primitive,
+    vrm::{HumanoidBone, Vrm},
+};
 
 #[derive(Resource, Default)]
 pub struct ComponentUis(pub HashMap<TypeId, Box<dyn Fn(&mut egui::Ui, EntityWorldMut<'_>)>>);
@@ -100,74 +112,56 @@ pub fn setup(mut component_uis: ResMut<ComponentUis>) {
     component_uis.0.insert(
         TypeId::of::<Handle<StandardMaterial>>(),
         Box::new(|ui, mut entity| {
-            let handle = entity
-                .get::<Handle<StandardMaterial>>()
-                .unwrap()
-                .clone_weak();
+            let id = entity.get::<Handle<StandardMaterial>>().unwrap().id();
             let world = unsafe { entity.world_mut() };
-            let mut materials = world
-                .get_resource_mut::<Assets<StandardMaterial>>()
-                .unwrap();
-
-            let material = materials.get_mut(&handle).unwrap();
-
-            ui.horizontal(|ui| {
-                let rgba = material.base_color.to_srgba().to_u8_array();
-                let mut color =
-                    egui::Color32::from_rgba_premultiplied(rgba[0], rgba[1], rgba[2], rgba[3]);
-
-                ui.label("Base Color");
-                if ui.color_edit_button_srgba(&mut color).changed() {
-                    material.base_color = Srgba::from_u8_array(color.to_array()).into();
-                }
-            });
-
-            ui.horizontal(|ui| {
-                let rgba = material.emissive.to_u8_array();
-                let mut color =
-                    egui::Color32::from_rgba_premultiplied(rgba[0], rgba[1], rgba[2], rgba[3]);
 
-                ui.label("Emissive");
-                if ui.color_edit_button_srgba(&mut color).changed() {
-                    material.emissive = LinearRgba::from_u8_array(color.to_array()).into();
+            let users = material_users(world).get(&id).copied().unwrap_or(0);
+            let mut selected = None;
+            let mut make_unique = false;
+
+            {
+                let library = world.resource::<MaterialLibrary>();
+                ComboBox::from_label("Material")
+                    .selected_text(library.name(id))
+                    .show_ui(ui, |ui| {
+                        for (other, name) in library.sorted() {
+                            if ui.selectable_label(other == id, name).clicked() {
+                                selected = Some(other);
+                            }
+                        }
+                    });
+
+                // Edits to a shared material change every entity using it
+                if users > 1 {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Shared by {users} entities"));
+                        make_unique = ui.button("Make Unique").clicked();
+                    });
                 }
-            });
-
-            ui.horizontal(|ui| {
-                ui.label("Emissive Exposure Weight");
-                ui.add(
-                    egui::DragValue::new(&mut material.emissive_exposure_weight)
-                        .range(0.0..=1.0)
-                        .speed(0.01),
-                );
-            });
+            }
 
-            ui.horizontal(|ui| {
-                ui.label("Perceptual Roughness");
-                ui.add(
-                    egui::DragValue::new(&mut material.perceptual_roughness)
-                        .range(0.089..=1.0)
-                        .speed(0.01),
-                );
-            });
-
-            ui.horizontal(|ui| {
-                ui.label("Metallic");
-                ui.add(
-                    egui::DragValue::new(&mut material.metallic)
-                        .range(0.0..=1.0)
-                        .speed(0.01),
-                );
+            // The library only holds weak handles to loaded materials
+            let mut assigned = selected.and_then(|other| {
+                world
+                    .resource_mut::<Assets<StandardMaterial>>()
+                    .get_strong_handle(other)
             });
+            if make_unique {
+                assigned = duplicate_material(world, id);
+            }
+            if let Some(handle) = assigned {
+                *entity.get_mut::<Handle<StandardMaterial>>().unwrap() = handle;
+                return;
+            }
 
-            ui.horizontal(|ui| {
-                ui.label("Reflectance");
-                ui.add(
-                    egui::DragValue::new(&mut material.reflectance)
-                        .range(0.0..=1.0)
-                        .speed(0.01),
-                );
-            });
+            let world = unsafe { entity.world_mut() };
+            let asset_server = world.resource::<AssetServer>().clone();
+            let mut materials = world
+                .get_resource_mut::<Assets<StandardMaterial>>()
+                .unwrap();
+            if let Some(material) = materials.get_mut(id) {
+                material_ui(ui, material, &asset_server);
+            }
         }),
     );
 
@@ -312,4 +306,558 @@ pub fn setup(mut component_uis: ResMut<ComponentUis>) {
             });
         }),
     );
+
+    component_uis.0.insert(
+        TypeId::of::<Primitive>(),
+        Box::new(|ui, mut entity| {
+            // Edit a copy so the mesh is only regenerated when a parameter changes
+            let mut primitive = entity.get::<Primitive>().unwrap().clone();
+
+            ComboBox::from_label("Shape")
+                .selected_text(primitive.name())
+                .show_ui(ui, |ui| {
+                    for shape in Primitive::ALL {
+                        if ui
+                            .selectable_label(primitive.is_shape(&shape), shape.name())
+                            .clicked()
+                            && !primitive.is_shape(&shape)
+                        {
+                            primitive = shape;
+                        }
+                    }
+                });
+
+            match &mut primitive {
+                Primitive::Cuboid { size } => {
+                    ui.label("Size");
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(&mut size.x).prefix("X ").speed(0.1));
+                        ui.add(DragValue::new(&mut size.y).prefix("Y ").speed(0.1));
+                        ui.add(DragValue::new(&mut size.z).prefix("Z ").speed(0.1));
+                    });
+                }
+                Primitive::Sphere {
+                    radius,
+                    sectors,
+                    stacks,
+                } => {
+                    length_ui(ui, "Radius", radius);
+                    resolution_ui(ui, "Segments", sectors, 3);
+                    resolution_ui(ui, "Rings", stacks, 2);
+                }
+                Primitive::Plane { size, subdivisions } => {
+                    ui.label("Size");
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(&mut size.x).prefix("X ").speed(0.1));
+                        ui.add(DragValue::new(&mut size.y).prefix("Z ").speed(0.1));
+                    });
+                    resolution_ui(ui, "Subdivisions", subdivisions, 0);
+                }
+                Primitive::Cylinder {
+                    radius,
+                    height,
+                    resolution,
+                    segments,
+                } => {
+                    length_ui(ui, "Radius", radius);
+                    length_ui(ui, "Height", height);
+                    resolution_ui(ui, "Resolution", resolution, 3);
+                    resolution_ui(ui, "Segments", segments, 1);
+                }
+                Primitive::Cone {
+                    radius,
+                    height,
+                    resolution,
+                } => {
+                    length_ui(ui, "Radius", radius);
+                    length_ui(ui, "Height", height);
+                    resolution_ui(ui, "Resolution", resolution, 3);
+                }
+                Primitive::Torus {
+                    minor_radius,
+                    major_radius,
+                    minor_resolution,
+                    major_resolution,
+                } => {
+                    length_ui(ui, "Minor Radius", minor_radius);
+                    length_ui(ui, "Major Radius", major_radius);
+                    resolution_ui(ui, "Minor Resolution", minor_resolution, 3);
+                    resolution_ui(ui, "Major Resolution", major_resolution, 3);
+                }
+                Primitive::Capsule {
+                    radius,
+                    length,
+                    longitudes,
+                    latitudes,
+                } => {
+                    length_ui(ui, "Radius", radius);
+                    length_ui(ui, "Length", length);
+                    resolution_ui(ui, "Segments", longitudes, 3);
+                    resolution_ui(ui, "Rings", latitudes, 2);
+                }
+                Primitive::Icosphere {
+                    radius,
+                    subdivisions,
+                } => {
+                    length_ui(ui, "Radius", radius);
+                    resolution_ui(ui, "Subdivisions", subdivisions, 0);
+                }
+                Primitive::Tetrahedron { size } => {
+                    length_ui(ui, "Size", size);
+                }
+                Primitive::Quad { size } => {
+                    ui.label("Size");
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(&mut size.x).prefix("X ").speed(0.1));
+                        ui.add(DragValue::new(&mut size.y).prefix("Y ").speed(0.1));
+                    });
+                }
+                Primitive::Annulus {
+                    inner_radius,
+                    outer_radius,
+                    resolution,
+                } => {
+                    length_ui(ui, "Inner Radius", inner_radius);
+                    length_ui(ui, "Outer Radius", outer_radius);
+                    resolution_ui(ui, "Resolution", resolution, 3);
+                }
+                Primitive::Extrusion {
+                    radius,
+                    sides,
+                    depth,
+                    segments,
+                } => {
+                    length_ui(ui, "Radius", radius);
+                    resolution_ui(ui, "Sides", sides, 3);
+                    length_ui(ui, "Depth", depth);
+                    resolution_ui(ui, "Segments", segments, 1);
+                }
+            }
+
+            if *entity.get::<Primitive>().unwrap() != primitive {
+                *entity.get_mut::<Primitive>().unwrap() = primitive;
+            }
+        }),
+    );
+
+    component_uis.0.insert(
+        TypeId::of::<Vrm>(),
+        Box::new(|ui, entity| {
+            let vrm = entity.get::<Vrm>().unwrap();
+
+            // Read only, the metadata belongs to the file
+            egui::Grid::new("VRM Metadata")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for (label, value) in [
+                        ("File", &vrm.path),
+                        ("VRM", &vrm.spec_version),
+                        ("Name", &vrm.name),
+                        ("Version", &vrm.version),
+                        ("Authors", &vrm.authors),
+                        ("Contact", &vrm.contact),
+                        ("References", &vrm.references),
+                        ("License", &vrm.license),
+                        ("Avatar Permission", &vrm.avatar_permission),
+                        ("Commercial Usage", &vrm.commercial_usage),
+                        ("Violent Usage", &vrm.violent_usage),
+                        ("Sexual Usage", &vrm.sexual_usage),
+                    ] {
+                        ui.label(label);
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                });
+        }),
+    );
+
+    component_uis.0.insert(
+        TypeId::of::<HumanoidBone>(),
+        Box::new(|ui, entity| {
+            ui.label(&entity.get::<HumanoidBone>().unwrap().0);
+        }),
+    );
+}
+
+/// The properties of a material, shared by the inspector and the materials tab
+pub fn material_ui(ui: &mut egui::Ui, material: &mut StandardMaterial, asset_server: &AssetServer) {
+    ui.horizontal(|ui| {
+        let rgba = material.base_color.to_srgba().to_u8_array();
+        let mut color = egui::Color32::from_rgba_premultiplied(rgba[0], rgba[1], rgba[2], rgba[3]);
+
+        ui.label("Base Color");
+        if ui.color_edit_button_srgba(&mut color).changed() {
+            material.base_color = Srgba::from_u8_array(color.to_array()).into();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        let rgba = material.emissive.to_u8_array();
+        let mut color = egui::Color32::from_rgba_premultiplied(rgba[0], rgba[1], rgba[2], rgba[3]);
+
+        ui.label("Emissive");
+        if ui.color_edit_button_srgba(&mut color).changed() {
+            material.emissive = LinearRgba::from_u8_array(color.to_array()).into();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Emissive Exposure Weight");
+        ui.add(
+            egui::DragValue::new(&mut material.emissive_exposure_weight)
+                .range(0.0..=1.0)
+                .speed(0.01),
+        );
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Perceptual Roughness");
+        ui.add(
+            egui::DragValue::new(&mut material.perceptual_roughness)
+                .range(0.089..=1.0)
+                .speed(0.01),
+        );
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Metallic");
+        ui.add(
+            egui::DragValue::new(&mut material.metallic)
+                .range(0.0..=1.0)
+                .speed(0.01),
+        );
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Reflectance");
+        ui.add(
+            egui::DragValue::new(&mut material.reflectance)
+                .range(0.0..=1.0)
+                .speed(0.01),
+        );
+    });
+
+    ui.collapsing("Textures", |ui| {
+        texture_ui(
+            ui,
+            "Base Color",
+            &mut material.base_color_texture,
+            asset_server,
+            true,
+        );
+        texture_ui(
+            ui,
+            "Normal Map",
+            &mut material.normal_map_texture,
+            asset_server,
+            false,
+        );
+        texture_ui(
+            ui,
+            "Metallic Roughness",
+            &mut material.metallic_roughness_texture,
+            asset_server,
+            false,
+        );
+        texture_ui(
+            ui,
+            "Occlusion",
+            &mut material.occlusion_texture,
+            asset_server,
+            false,
+        );
+        texture_ui(
+            ui,
+            "Emissive",
+            &mut material.emissive_texture,
+            asset_server,
+            true,
+        );
+
+        ui.horizontal(|ui| {
+            ui.label("Flip Normal Map Y");
+            ui.checkbox(&mut material.flip_normal_map_y, "");
+        });
+    });
+
+    ui.collapsing("Rendering", |ui| {
+        ComboBox::from_label("Alpha Mode")
+            .selected_text(alpha_mode_name(&material.alpha_mode))
+            .show_ui(ui, |ui| {
+                for alpha_mode in [
+                    AlphaMode::Opaque,
+                    AlphaMode::Mask(0.5),
+                    AlphaMode::Blend,
+                    AlphaMode::Premultiplied,
+                    AlphaMode::AlphaToCoverage,
+                    AlphaMode::Add,
+                    AlphaMode::Multiply,
+                ] {
+                    let name = alpha_mode_name(&alpha_mode);
+                    let selected = alpha_mode_name(&material.alpha_mode) == name;
+                    if ui.selectable_label(selected, name).clicked() && !selected {
+                        material.alpha_mode = alpha_mode;
+                    }
+                }
+            });
+
+        if let AlphaMode::Mask(cutoff) = &mut material.alpha_mode {
+            value_ui(ui, "Alpha Cutoff", cutoff, 0.0..=1.0);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Double Sided");
+            // Back faces are only lit correctly when they aren't culled
+            if ui.checkbox(&mut material.double_sided, "").changed() && material.double_sided {
+                material.cull_mode = None;
+            }
+        });
+
+        ComboBox::from_label("Cull Mode")
+            .selected_text(match material.cull_mode {
+                None => "None",
+                Some(Face::Front) => "Front",
+                Some(Face::Back) => "Back",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut material.cull_mode, None, "None");
+                ui.selectable_value(&mut material.cull_mode, Some(Face::Front), "Front");
+                ui.selectable_value(&mut material.cull_mode, Some(Face::Back), "Back");
+            });
+
+        ui.horizontal(|ui| {
+            ui.label("Unlit");
+            ui.checkbox(&mut material.unlit, "");
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Depth Bias");
+            ui.add(DragValue::new(&mut material.depth_bias).speed(0.1));
+        });
+    });
+
+    ui.collapsing("UV Transform", |ui| {
+        let original = material.uv_transform.to_scale_angle_translation();
+        let (mut scale, mut angle, mut translation) = original;
+        let mut degrees = angle.to_degrees();
+
+        ui.horizontal(|ui| {
+            ui.label("Offset");
+            ui.add(DragValue::new(&mut translation.x).prefix("U ").speed(0.01));
+            ui.add(DragValue::new(&mut translation.y).prefix("V ").speed(0.01));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Scale");
+            ui.add(DragValue::new(&mut scale.x).prefix("U ").speed(0.01));
+            ui.add(DragValue::new(&mut scale.y).prefix("V ").speed(0.01));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Rotation");
+            if ui.add(DragValue::new(&mut degrees).suffix("°")).changed() {
+                angle = degrees.to_radians();
+            }
+        });
+
+        // Composing the parts again isn't exact, so only edits are written back
+        if (scale, angle, translation) != original {
+            material.uv_transform =
+                Affine2::from_scale_angle_translation(scale, angle, translation);
+        }
+    });
+
+    ui.collapsing("Parallax", |ui| {
+        texture_ui(
+            ui,
+            "Depth Map",
+            &mut material.depth_map,
+            asset_server,
+            false,
+        );
+        value_ui(
+            ui,
+            "Depth Scale",
+            &mut material.parallax_depth_scale,
+            0.0..=1.0,
+        );
+        value_ui(
+            ui,
+            "Max Layer Count",
+            &mut material.max_parallax_layer_count,
+            1.0..=256.0,
+        );
+
+        let mut relief_steps = match material.parallax_mapping_method {
+            ParallaxMappingMethod::Occlusion => None,
+            ParallaxMappingMethod::Relief { max_steps } => Some(max_steps),
+        };
+        ComboBox::from_label("Mapping Method")
+            .selected_text(match relief_steps {
+                None => "Occlusion",
+                Some(_) => "Relief",
+            })
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(relief_steps.is_none(), "Occlusion")
+                    .clicked()
+                {
+                    relief_steps = None;
+                }
+                if ui
+                    .selectable_label(relief_steps.is_some(), "Relief")
+                    .clicked()
+                    && relief_steps.is_none()
+                {
+                    relief_steps = Some(5);
+                }
+            });
+        if let Some(max_steps) = &mut relief_steps {
+            resolution_ui(ui, "Max Steps", max_steps, 1);
+        }
+
+        let method = match relief_steps {
+            None => ParallaxMappingMethod::Occlusion,
+            Some(max_steps) => ParallaxMappingMethod::Relief { max_steps },
+        };
+        if method != material.parallax_mapping_method {
+            material.parallax_mapping_method = method;
+        }
+    });
+
+    ui.collapsing("Clearcoat", |ui| {
+        value_ui(ui, "Clearcoat", &mut material.clearcoat, 0.0..=1.0);
+        value_ui(
+            ui,
+            "Roughness",
+            &mut material.clearcoat_perceptual_roughness,
+            0.089..=1.0,
+        );
+    });
+
+    ui.collapsing("Anisotropy", |ui| {
+        value_ui(ui, "Strength", &mut material.anisotropy_strength, 0.0..=1.0);
+
+        let mut degrees = material.anisotropy_rotation.to_degrees();
+        ui.horizontal(|ui| {
+            ui.label("Rotation");
+            if ui.add(DragValue::new(&mut degrees).suffix("°")).changed() {
+                material.anisotropy_rotation = degrees.to_radians();
+            }
+        });
+    });
+
+    ui.collapsing("Transmission", |ui| {
+        value_ui(
+            ui,
+            "Specular Transmission",
+            &mut material.specular_transmission,
+            0.0..=1.0,
+        );
+        value_ui(
+            ui,
+            "Diffuse Transmission",
+            &mut material.diffuse_transmission,
+            0.0..=1.0,
+        );
+        value_ui(ui, "Thickness", &mut material.thickness, 0.0..=f32::MAX);
+        value_ui(ui, "IOR", &mut material.ior, 1.0..=4.0);
+
+        ui.horizontal(|ui| {
+            ui.label("Attenuation Distance");
+            ui.add(
+                DragValue::new(&mut material.attenuation_distance)
+                    .range(0.0..=f32::INFINITY)
+                    .speed(0.1),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            let rgba = material.attenuation_color.to_srgba().to_u8_array();
+            let mut color =
+                egui::Color32::from_rgba_premultiplied(rgba[0], rgba[1], rgba[2], rgba[3]);
+
+            ui.label("Attenuation Color");
+            if ui.color_edit_button_srgba(&mut color).changed() {
+                material.attenuation_color = Srgba::from_u8_array(color.to_array()).into();
+            }
+        });
+    });
+}
+
+fn alpha_mode_name(alpha_mode: &AlphaMode) -> &'static str {
+    match alpha_mode {
+        AlphaMode::Opaque => "Opaque",
+        AlphaMode::Mask(_) => "Mask",
+        AlphaMode::Blend => "Blend",
+        AlphaMode::Premultiplied => "Premultiplied",
+        AlphaMode::AlphaToCoverage => "Alpha To Coverage",
+        AlphaMode::Add => "Add",
+        AlphaMode::Multiply => "Multiply",
+    }
+}
+
+/// A texture slot showing the file name, loaded from disk through the asset server.
+/// Data textures like normal maps aren't `srgb`
+fn texture_ui(
+    ui: &mut egui::Ui,
+    label: &str,
+    texture: &mut Option<Handle<Image>>,
+    asset_server: &AssetServer,
+    srgb: bool,
+) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+
+        let file_name = texture.as_ref().map(|handle| {
+            handle
+                .path()
+                .and_then(|path| path.path().file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Embedded".to_string())
+        });
+        ui.weak(file_name.as_deref().unwrap_or("None"));
+
+        if ui.button("Load").clicked() {
+            let mut dialog = rfd::FileDialog::new().add_filter(
+                "Image",
+                &["png", "jpg", "jpeg", "ktx2", "hdr", "tga", "bmp"],
+            );
+            if let Ok(path) = std::env::current_dir() {
+                dialog = dialog.set_directory(path);
+            }
+
+            if let Some(path) = dialog.pick_file() {
+                *texture = Some(
+                    asset_server
+                        .load_with_settings(path, move |settings: &mut ImageLoaderSettings| {
+                            settings.is_srgb = srgb
+                        }),
+                );
+            }
+        }
+
+        if texture.is_some() && ui.button("Clear").clicked() {
+            *texture = None;
+        }
+    });
+}
+
+fn value_ui(ui: &mut egui::Ui, label: &str, value: &mut f32, range: RangeInclusive<f32>) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(DragValue::new(value).range(range).speed(0.01));
+    });
+}
+
+fn length_ui(ui: &mut egui::Ui, label: &str, value: &mut f32) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(DragValue::new(value).range(0.0..=f32::MAX).speed(0.01));
+    });
+}
+
+fn resolution_ui(ui: &mut egui::Ui, label: &str, value: &mut u32, min: u32) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(DragValue::new(value).range(min..=512));
+    });
 }
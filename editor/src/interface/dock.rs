@@ -1,11 +1,23 @@
+mod assets;
 mod entities;
 mod inspector;
+mod materials;
 mod view;
 
-use bevy::prelude::*;
+pub use assets::{scan_assets, AssetBrowser};
+pub use entities::OutlinerState;
+pub use inspector::CopiedComponent;
+pub use materials::MaterialsState;
+
+use bevy::{prelude::*, utils::HashMap};
 use bevy_egui::egui;
 use egui_dock::TabViewer;
 
+use crate::{
+    camera::{editor_camera, set_active_camera, ActiveCamera, Flycam},
+    EditorAction,
+};
+
 pub struct AddTab {
     pub tab: InterfaceTab,
     pub surface: egui_dock::SurfaceIndex,
@@ -13,15 +25,18 @@ pub struct AddTab {
 }
 
 pub enum InterfaceTab {
-    Viewport,
+    /// A view of the scene through an editor camera
+    Viewport(Entity),
     Entities,
     Inspector,
+    Materials,
+    Assets,
     ViewSettings,
 }
 
 pub struct InterfaceTabViewer<'a> {
     pub world: &'a mut World,
-    pub viewport_rect: &'a mut egui::Rect,
+    pub viewport_rects: &'a mut HashMap<Entity, egui::Rect>,
     pub cursor_over_ui: &'a mut bool,
     pub added_tabs: &'a mut Vec<AddTab>,
 }
@@ -31,18 +46,30 @@ impl TabViewer for InterfaceTabViewer<'_> {
 
     fn title(&mut self, tab: &mut Self::Tab) -> bevy_egui::egui::WidgetText {
         match tab {
-            InterfaceTab::Viewport => "Viewport".into(),
+            InterfaceTab::Viewport(_) => "Viewport".into(),
             InterfaceTab::Entities => "Entities".into(),
             InterfaceTab::Inspector => "Inspector".into(),
+            InterfaceTab::Materials => "Materials".into(),
+            InterfaceTab::Assets => "Assets".into(),
             InterfaceTab::ViewSettings => "View Settings".into(),
         }
     }
 
     fn ui(&mut self, ui: &mut bevy_egui::egui::Ui, tab: &mut Self::Tab) {
         match tab {
-            InterfaceTab::Viewport => {
-                *self.cursor_over_ui = !ui.rect_contains_pointer(ui.clip_rect());
-                *self.viewport_rect = ui.clip_rect();
+            InterfaceTab::Viewport(camera) => {
+                let camera = *camera;
+                self.viewport_rects.insert(camera, ui.clip_rect());
+
+                if ui.rect_contains_pointer(ui.clip_rect()) {
+                    *self.cursor_over_ui = false;
+
+                    // Don't switch cameras in the middle of an action
+                    let editor_action = self.world.resource::<EditorAction>();
+                    if editor_action.is_none_or(|v| v == crate::GUI_ACTION_ID) {
+                        set_active_camera(self.world, camera);
+                    }
+                }
 
                 // FPS
                 let time = self.world.resource::<Time<Virtual>>();
@@ -52,6 +79,12 @@ impl TabViewer for InterfaceTabViewer<'_> {
                 ui.label(
                     egui::RichText::new(format!("fps: {}", trunc_fps)).color(egui::Color32::WHITE),
                 );
+
+                if view::orientation(self.world, ui, camera) {
+                    *self.cursor_over_ui = true;
+                }
+
+                assets::viewport_drop(self.world, ui, camera);
             }
             InterfaceTab::Entities => {
                 entities::show(self.world, ui);
@@ -59,18 +92,54 @@ impl TabViewer for InterfaceTabViewer<'_> {
             InterfaceTab::Inspector => {
                 inspector::show(self.world, ui);
             }
+            InterfaceTab::Materials => {
+                materials::show(self.world, ui);
+            }
+            InterfaceTab::Assets => {
+                assets::show(self.world, ui);
+            }
             InterfaceTab::ViewSettings => {
                 view::settings(self.world, ui);
             }
         }
     }
 
+    fn id(&mut self, tab: &mut Self::Tab) -> egui::Id {
+        match tab {
+            InterfaceTab::Viewport(camera) => egui::Id::new(("Viewport", *camera)),
+            _ => egui::Id::new(self.title(tab).text()),
+        }
+    }
+
     fn clear_background(&self, tab: &Self::Tab) -> bool {
-        !matches!(tab, InterfaceTab::Viewport)
+        !matches!(tab, InterfaceTab::Viewport(_))
     }
 
     fn closeable(&mut self, tab: &mut Self::Tab) -> bool {
-        !matches!(tab, InterfaceTab::Viewport)
+        // Keep at least one viewport open
+        match tab {
+            InterfaceTab::Viewport(_) => {
+                let mut cameras = self.world.query_filtered::<(), With<Flycam>>();
+                cameras.iter(self.world).count() > 1
+            }
+            _ => true,
+        }
+    }
+
+    fn on_close(&mut self, tab: &mut Self::Tab) -> bool {
+        if let InterfaceTab::Viewport(camera) = tab {
+            self.world.despawn(*camera);
+
+            let mut active = self.world.query_filtered::<(), With<ActiveCamera>>();
+            if active.iter(self.world).next().is_none() {
+                let mut cameras = self.world.query_filtered::<Entity, With<Flycam>>();
+                if let Some(camera) = cameras.iter(self.world).next() {
+                    set_active_camera(self.world, camera);
+                }
+            }
+        }
+
+        true
     }
 
     fn add_popup(
@@ -82,10 +151,16 @@ impl TabViewer for InterfaceTabViewer<'_> {
         ui.set_min_width(120.0);
         ui.style_mut().visuals.button_frame = false;
 
-        let tab = if ui.button("Entities").clicked() {
+        let tab = if ui.button("Viewport").clicked() {
+            InterfaceTab::Viewport(new_viewport_camera(self.world))
+        } else if ui.button("Entities").clicked() {
             InterfaceTab::Entities
         } else if ui.button("Inspector").clicked() {
             InterfaceTab::Inspector
+        } else if ui.button("Materials").clicked() {
+            InterfaceTab::Materials
+        } else if ui.button("Assets").clicked() {
+            InterfaceTab::Assets
         } else if ui.button("View Settings").clicked() {
             InterfaceTab::ViewSettings
         } else {
@@ -95,3 +170,24 @@ impl TabViewer for InterfaceTabViewer<'_> {
         self.added_tabs.push(AddTab { tab, surface, node });
     }
 }
+
+/// Spawns an editor camera for a new viewport, starting at the active camera's view
+fn new_viewport_camera(world: &mut World) -> Entity {
+    let order = world
+        .query_filtered::<&Camera, With<Flycam>>()
+        .iter(world)
+        .map(|camera| camera.order)
+        .max()
+        .unwrap_or(-1)
+        + 1;
+
+    let (transform, focus) = world
+        .query_filtered::<(&Transform, &Flycam), With<ActiveCamera>>()
+        .get_single(world)
+        .map(|(transform, flycam)| (*transform, flycam.focus))
+        .unwrap_or_default();
+
+    let camera = world.spawn(editor_camera(order, transform)).id();
+    world.get_mut::<Flycam>(camera).unwrap().focus = focus;
+    camera
+}
@@ -0,0 +1,391 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    math::bounding::{Aabb3d, RayCast3d},
+    prelude::*,
+    render::primitives::Aabb,
+    utils::HashMap,
+};
+use bevy_egui::{egui, EguiSettings, EguiUserTextures};
+
+use crate::{
+    assets_folder, interface::gltf_import::GltfImport, mesh_file, prefab, world_bounds,
+    EditorEntity, SelectedEntities,
+};
+
+const TILE_SIZE: f32 = 72.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AssetKind {
+    Folder,
+    Gltf,
+    Image,
+    Scene,
+    Prefab,
+    Mesh,
+    Other,
+}
+
+impl AssetKind {
+    fn from_path(path: &Path) -> Self {
+        if path.is_dir() {
+            return Self::Folder;
+        }
+
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if file_name.ends_with(".prefab.ron") {
+            return Self::Prefab;
+        }
+        if file_name.ends_with(".scn.ron") {
+            return Self::Scene;
+        }
+
+        match file_name.rsplit_once('.').map(|(_, extension)| extension) {
+            Some("gltf" | "glb") => Self::Gltf,
+            Some("obj" | "stl" | "ply") => Self::Mesh,
+            Some("png" | "jpg" | "jpeg" | "ktx2" | "hdr" | "tga" | "bmp") => Self::Image,
+            _ => Self::Other,
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Folder => "📁",
+            Self::Gltf => "🗿",
+            Self::Image => "🖼",
+            Self::Scene => "🎬",
+            Self::Prefab => "📦",
+            Self::Mesh => "🔷",
+            Self::Other => "📄",
+        }
+    }
+
+    /// Whether the asset can be dragged into a viewport
+    fn can_place(self) -> bool {
+        matches!(self, Self::Gltf | Self::Scene | Self::Prefab | Self::Mesh)
+    }
+}
+
+pub struct AssetEntry {
+    /// Relative to the assets directory
+    pub path: PathBuf,
+    pub name: String,
+    pub kind: AssetKind,
+}
+
+/// An asset dragged from the asset browser
+#[derive(Clone)]
+struct DraggedAsset {
+    path: PathBuf,
+    kind: AssetKind,
+}
+
+#[derive(Resource)]
+pub struct AssetBrowser {
+    /// The `assets` directory the asset server loads from
+    pub root: PathBuf,
+    /// The shown folder, relative to `root`
+    pub current: PathBuf,
+    pub entries: Vec<AssetEntry>,
+    pub filter: String,
+    /// Keeps the images of the shown folder loaded for their thumbnails
+    thumbnails: HashMap<PathBuf, Handle<Image>>,
+}
+
+impl Default for AssetBrowser {
+    fn default() -> Self {
+        let mut browser = Self {
+            root: assets_folder(),
+            current: PathBuf::new(),
+            entries: Vec::new(),
+            filter: String::new(),
+            thumbnails: HashMap::new(),
+        };
+        browser.scan();
+        browser
+    }
+}
+
+impl AssetBrowser {
+    /// Reads the entries of the shown folder, folders first
+    pub fn scan(&mut self) {
+        self.entries.clear();
+
+        let Ok(read_dir) = std::fs::read_dir(self.root.join(&self.current)) else {
+            return;
+        };
+
+        for dir_entry in read_dir.flatten() {
+            let name = dir_entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name.ends_with(".meta") {
+                continue;
+            }
+
+            self.entries.push(AssetEntry {
+                path: self.current.join(&name),
+                kind: AssetKind::from_path(&dir_entry.path()),
+                name,
+            });
+        }
+
+        self.entries.sort_by(|a, b| {
+            (a.kind != AssetKind::Folder, a.name.to_lowercase())
+                .cmp(&(b.kind != AssetKind::Folder, b.name.to_lowercase()))
+        });
+    }
+
+    fn open(&mut self, folder: PathBuf) {
+        self.current = folder;
+        self.thumbnails.clear();
+        self.scan();
+    }
+}
+
+/// Picks up files added, removed or renamed outside the editor
+pub fn scan_assets(mut browser: ResMut<AssetBrowser>) {
+    browser.scan();
+}
+
+pub fn show(world: &mut World, ui: &mut egui::Ui) {
+    world.resource_scope::<AssetBrowser, _>(|world, mut browser| {
+        ui.horizontal(|ui| {
+            let parent = browser.current.parent().map(Path::to_path_buf);
+            if ui
+                .add_enabled(parent.is_some(), egui::Button::new("⬆"))
+                .on_hover_text("Parent folder")
+                .clicked()
+            {
+                browser.open(parent.unwrap_or_default());
+            }
+
+            ui.label(format!("assets/{}", browser.current.to_string_lossy()));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut browser.filter)
+                        .hint_text("Filter")
+                        .desired_width(120.0),
+                );
+            });
+        });
+
+        ui.separator();
+
+        if !browser.root.is_dir() {
+            ui.label(format!("{:?} doesn't exist.", browser.root));
+            return;
+        }
+
+        let filter = browser.filter.to_lowercase();
+        let mut opened = None;
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    let browser = &mut *browser;
+                    for entry in browser.entries.iter() {
+                        if !entry.name.to_lowercase().contains(&filter) {
+                            continue;
+                        }
+
+                        let texture =
+                            (entry.kind == AssetKind::Image).then(|| {
+                                let handle =
+                                    browser.thumbnails.entry(entry.path.clone()).or_insert_with(
+                                        || world.resource::<AssetServer>().load(entry.path.clone()),
+                                    );
+                                world
+                                    .resource_mut::<EguiUserTextures>()
+                                    .add_image(handle.clone_weak())
+                            });
+
+                        let response = tile(ui, entry, texture);
+                        if response.double_clicked() && entry.kind == AssetKind::Folder {
+                            opened = Some(entry.path.clone());
+                        }
+
+                        if entry.kind.can_place() {
+                            response.dnd_set_drag_payload(DraggedAsset {
+                                path: entry.path.clone(),
+                                kind: entry.kind,
+                            });
+
+                            if response.dragged() {
+                                egui::show_tooltip_at_pointer(
+                                    ui.ctx(),
+                                    ui.layer_id(),
+                                    response.id,
+                                    |ui| ui.label(format!("{} {}", entry.kind.icon(), entry.name)),
+                                );
+                            }
+                        }
+                    }
+                });
+            });
+
+        if let Some(folder) = opened {
+            browser.open(folder);
+        }
+    });
+}
+
+/// A thumbnail or icon with the file name below it
+fn tile(ui: &mut egui::Ui, entry: &AssetEntry, texture: Option<egui::TextureId>) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(TILE_SIZE, TILE_SIZE + 16.0),
+        egui::Sense::click_and_drag(),
+    );
+
+    if response.hovered() {
+        ui.painter()
+            .rect_filled(rect, 4.0, ui.visuals().widgets.hovered.weak_bg_fill);
+    }
+
+    let icon_rect = egui::Rect::from_min_size(rect.min, egui::Vec2::splat(TILE_SIZE)).shrink(6.0);
+    match texture {
+        Some(texture) => {
+            egui::Image::new(egui::load::SizedTexture::new(texture, icon_rect.size()))
+                .maintain_aspect_ratio(true)
+                .paint_at(ui, icon_rect);
+        }
+        None => {
+            ui.painter().text(
+                icon_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                entry.kind.icon(),
+                egui::FontId::proportional(32.0),
+                ui.visuals().text_color(),
+            );
+        }
+    }
+
+    let name = if entry.name.chars().count() > 11 {
+        format!("{}…", entry.name.chars().take(10).collect::<String>())
+    } else {
+        entry.name.clone()
+    };
+    ui.painter().text(
+        rect.center_bottom() - egui::vec2(0.0, 2.0),
+        egui::Align2::CENTER_BOTTOM,
+        name,
+        egui::FontId::proportional(11.0),
+        ui.visuals().text_color(),
+    );
+
+    response.on_hover_text(&entry.name)
+}
+
+/// Places assets dropped on the viewport of `camera` at the surface under the pointer
+pub fn viewport_drop(world: &mut World, ui: &mut egui::Ui, camera: Entity) {
+    let rect = ui.clip_rect();
+    let response = ui.interact(
+        rect,
+        egui::Id::new(("Viewport Drop", camera)),
+        egui::Sense::hover(),
+    );
+
+    let Some(dragged) = response.dnd_release_payload::<DraggedAsset>() else {
+        return;
+    };
+    let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+        return;
+    };
+
+    // Egui points to logical viewport pixels
+    let scale_factor = world.resource::<EguiSettings>().scale_factor;
+    let viewport_position = (pointer - rect.min) * scale_factor;
+
+    let translation = drop_point(
+        world,
+        camera,
+        Vec2::new(viewport_position.x, viewport_position.y),
+    );
+    let transform = Transform::from_translation(translation);
+
+    let name = dragged
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = name.split('.').next().unwrap_or_default().to_string();
+
+    let entity = match dragged.kind {
+        AssetKind::Gltf => {
+            // The import popup picks the scene or meshes and selects what it spawns
+            let gltf = world.resource::<AssetServer>().load(dragged.path.clone());
+            world.insert_resource(GltfImport {
+                translation: Some(translation),
+                ..GltfImport::new(dragged.path, gltf)
+            });
+            return;
+        }
+        AssetKind::Scene => {
+            let scene = world.resource::<AssetServer>().load(dragged.path.clone());
+            world
+                .spawn((
+                    DynamicSceneBundle {
+                        scene,
+                        transform,
+                        ..default()
+                    },
+                    Name::new(name),
+                ))
+                .id()
+        }
+        AssetKind::Prefab => {
+            let path = world.resource::<AssetBrowser>().root.join(&dragged.path);
+            let Some(root) = prefab::instantiate(world, &path) else {
+                return;
+            };
+            if let Some(mut root_transform) = world.get_mut::<Transform>(root) {
+                root_transform.translation = translation;
+            }
+            root
+        }
+        AssetKind::Mesh => mesh_file::import(world, &dragged.path, transform),
+        _ => return,
+    };
+
+    let mut selected = world.resource_mut::<SelectedEntities>();
+    selected.0.clear();
+    selected.0.insert(entity);
+}
+
+/// The nearest bounds hit by a ray through `viewport_position`,
+/// the ground plane, or a point in front of the camera
+fn drop_point(world: &mut World, camera: Entity, viewport_position: Vec2) -> Vec3 {
+    let Some(ray) = world
+        .get::<Camera>(camera)
+        .zip(world.get::<GlobalTransform>(camera))
+        .and_then(|(camera, global_transform)| {
+            camera.viewport_to_world(global_transform, viewport_position)
+        })
+    else {
+        return Vec3::ZERO;
+    };
+
+    let ray_cast = RayCast3d::from_ray(ray, f32::MAX);
+    let hit = world
+        .query_filtered::<(&GlobalTransform, &Aabb), Without<EditorEntity>>()
+        .iter(world)
+        .filter_map(|(global_transform, aabb)| {
+            let (min, max) = world_bounds(aabb, global_transform);
+            ray_cast.aabb_intersection_at(&Aabb3d {
+                min: min.into(),
+                max: max.into(),
+            })
+        })
+        // The camera is inside the bounds the ray hits at 0
+        .filter(|&distance| distance > 0.0)
+        .reduce(f32::min);
+
+    let distance = hit
+        .or_else(|| ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y)))
+        .unwrap_or(5.0);
+    ray.get_point(distance)
+}
@@ -1,19 +1,93 @@
-use bevy::{ecs::observer::ObserverState, prelude::*};
+use bevy::{ecs::observer::ObserverState, prelude::*, utils::HashSet};
 use bevy_egui::egui::{self, collapsing_header::CollapsingState};
 
-use crate::{observers::SelectEntity, EditorEntity, SelectedEntities};
+use crate::{
+    camera::FrameEntities,
+    observers::{DeleteSelected, DuplicateSelected, Reparent, SelectEntity},
+    prefab::PrefabInstance,
+    EditorEntity, Locked, SelectedEntities,
+};
+
+use super::inspector::get_component_name;
+
+#[derive(Resource, Default)]
+pub struct OutlinerState {
+    /// Space separated name search and `has:Component` filters
+    pub filter: String,
+    /// The entity being renamed and its new name
+    pub renaming: Option<(Entity, String)>,
+}
+
+/// The drag and drop payload of an entity row
+struct DraggedEntity(Entity);
+
+/// The entities shown while filtering
+struct Filter {
+    /// Entities that match the filter
+    matches: HashSet<Entity>,
+    /// Matches and their ancestors
+    visible: HashSet<Entity>,
+}
 
 pub fn show(world: &mut World, ui: &mut egui::Ui) {
     let mut roots = world.query_filtered::<Entity, (Without<Parent>, Without<EditorEntity>)>();
     let mut info_query =
         world.query_filtered::<(Option<&Name>, Option<&Children>), Without<EditorEntity>>();
 
+    ui.horizontal(|ui| {
+        let mut state = world.resource_mut::<OutlinerState>();
+        if !state.filter.is_empty() && ui.button("✖").clicked() {
+            state.filter.clear();
+        }
+
+        ui.add(
+            egui::TextEdit::singleline(&mut state.filter)
+                .hint_text("Filter (name has:Component)")
+                .desired_width(f32::INFINITY),
+        );
+    });
+
+    let filter_text = world.resource::<OutlinerState>().filter.clone();
+    let filter = build_filter(world, &filter_text);
+
+    let mut reparent = None;
     egui::ScrollArea::vertical().show(ui, |ui| {
         let entities = roots.iter(world).collect::<Vec<Entity>>();
         for entity in entities {
-            show_entity(ui, world, &mut info_query, entity);
+            show_entity(
+                ui,
+                world,
+                &mut info_query,
+                entity,
+                filter.as_ref(),
+                &mut reparent,
+            );
+        }
+
+        // Dropping below the entities moves to the root
+        let response = ui.allocate_response(
+            egui::vec2(ui.available_width(), ui.available_height().max(24.0)),
+            egui::Sense::hover(),
+        );
+        if response.dnd_hover_payload::<DraggedEntity>().is_some() {
+            ui.painter().rect_filled(
+                response.rect,
+                2.0,
+                ui.visuals().selection.bg_fill.gamma_multiply(0.25),
+            );
+        }
+        if let Some(dragged) = response.dnd_release_payload::<DraggedEntity>() {
+            reparent = Some(Reparent {
+                entity: dragged.0,
+                parent: None,
+                index: None,
+            });
         }
     });
+
+    if let Some(reparent) = reparent {
+        world.trigger(reparent);
+    }
 }
 
 fn show_entity(
@@ -21,46 +95,356 @@ fn show_entity(
     world: &mut World,
     info_query: &mut QueryState<(Option<&Name>, Option<&Children>), Without<EditorEntity>>,
     entity: Entity,
+    filter: Option<&Filter>,
+    reparent: &mut Option<Reparent>,
 ) {
     if is_hidden_entity(world.entity(entity)) {
         return;
     }
 
+    if filter.is_some_and(|filter| !filter.visible.contains(&entity)) {
+        return;
+    }
+
     let Ok((name, children)) = info_query.get(world, entity) else {
         return;
     };
 
-    let is_selected = world.resource::<SelectedEntities>().0.contains(&entity);
     let label = if let Some(name) = name {
         format!("{} ({})", name, entity)
     } else {
         entity.to_string()
     };
 
+    // Prefab instances are marked with a box
+    let label = if world.get::<PrefabInstance>(entity).is_some() {
+        format!("📦 {label}")
+    } else {
+        label
+    };
+
+    // Ancestors that are only shown because of a matching descendant are dimmed
+    let is_match = filter.is_none_or(|filter| filter.matches.contains(&entity));
+    let label = if is_match {
+        egui::RichText::new(label)
+    } else {
+        egui::RichText::new(label).weak()
+    };
+
     if let Some(children) = children {
         let children = children.iter().cloned().collect::<Vec<Entity>>();
 
-        CollapsingState::load_with_default_open(ui.ctx(), entity.to_string().into(), false)
+        let mut state =
+            CollapsingState::load_with_default_open(ui.ctx(), entity.to_string().into(), false);
+
+        // Expand ancestors of matches
+        if let Some(filter) = filter {
+            if children.iter().any(|child| filter.visible.contains(child)) {
+                state.set_open(true);
+            }
+        }
+
+        state
             .show_header(ui, |ui| {
-                if ui.selectable_label(is_selected, label).clicked() {
-                    world.trigger(SelectEntity { target: entity });
-                }
+                entity_label(ui, world, entity, label, reparent);
             })
             .body(|ui| {
                 for child in children {
-                    show_entity(ui, world, info_query, child);
+                    show_entity(ui, world, info_query, child, filter, reparent);
                 }
             });
     } else {
         ui.horizontal(|ui| {
             ui.add_space(20.0); // Pad entities with no children to align
-            if ui.selectable_label(is_selected, label).clicked() {
-                world.trigger(SelectEntity { target: entity });
-            }
+            entity_label(ui, world, entity, label, reparent);
         });
     }
 }
 
+/// A selectable label with visibility and lock toggles, a context menu,
+/// and drag and drop onto other labels to reparent
+fn entity_label(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    entity: Entity,
+    label: egui::RichText,
+    reparent: &mut Option<Reparent>,
+) {
+    toggles(ui, world, entity);
+
+    if world
+        .resource::<OutlinerState>()
+        .renaming
+        .as_ref()
+        .is_some_and(|(e, _)| *e == entity)
+    {
+        rename(ui, world, entity);
+        return;
+    }
+
+    let is_selected = world.resource::<SelectedEntities>().0.contains(&entity);
+    let response = ui
+        .selectable_label(is_selected, label)
+        .interact(egui::Sense::click_and_drag());
+
+    if response.double_clicked() {
+        start_rename(world, entity);
+    } else if response.clicked() {
+        world.trigger(SelectEntity { target: entity });
+    }
+
+    response.context_menu(|ui| context_menu(ui, world, entity));
+    response.dnd_set_drag_payload(DraggedEntity(entity));
+
+    let Some(dragged) = response.dnd_hover_payload::<DraggedEntity>() else {
+        return;
+    };
+    if dragged.0 == entity {
+        return;
+    }
+
+    // The top and bottom of a label insert next to it, the middle inserts into it
+    let rect = response.rect;
+    let pointer_y = ui
+        .ctx()
+        .pointer_hover_pos()
+        .map_or(rect.center().y, |p| p.y);
+    let t = (pointer_y - rect.top()) / rect.height();
+    let stroke = ui.visuals().selection.stroke;
+
+    let parent = world.get::<Parent>(entity).map(|parent| parent.get());
+    let drop = if t < 0.25 || t > 0.75 {
+        let y = if t < 0.25 { rect.top() } else { rect.bottom() };
+        ui.painter().hline(rect.x_range(), y, stroke);
+
+        let index = parent.and_then(|parent| {
+            let siblings = world.get::<Children>(parent)?;
+            let index = siblings
+                .iter()
+                .filter(|&&sibling| sibling != dragged.0)
+                .position(|&sibling| sibling == entity)?;
+            Some(if t < 0.25 { index } else { index + 1 })
+        });
+
+        Reparent {
+            entity: dragged.0,
+            parent,
+            index,
+        }
+    } else {
+        ui.painter().rect_stroke(rect, 2.0, stroke);
+
+        Reparent {
+            entity: dragged.0,
+            parent: Some(entity),
+            index: None,
+        }
+    };
+
+    if response.dnd_release_payload::<DraggedEntity>().is_some() {
+        *reparent = Some(drop);
+    }
+}
+
+/// The visibility and lock buttons of a row
+fn toggles(ui: &mut egui::Ui, world: &mut World, entity: Entity) {
+    let mut entity_mut = world.entity_mut(entity);
+
+    if let Some(mut visibility) = entity_mut.get_mut::<Visibility>() {
+        let is_hidden = *visibility == Visibility::Hidden;
+        let icon = egui::RichText::new("👁");
+        let icon = if is_hidden { icon.weak() } else { icon };
+
+        if ui
+            .add(egui::Button::new(icon).frame(false))
+            .on_hover_text(if is_hidden { "Show" } else { "Hide" })
+            .clicked()
+        {
+            *visibility = if is_hidden {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+
+    let is_locked = entity_mut.contains::<Locked>();
+    let icon = if is_locked {
+        egui::RichText::new("🔒")
+    } else {
+        egui::RichText::new("🔓").weak()
+    };
+
+    if ui
+        .add(egui::Button::new(icon).frame(false))
+        .on_hover_text(if is_locked { "Unlock" } else { "Lock" })
+        .clicked()
+    {
+        if is_locked {
+            entity_mut.remove::<Locked>();
+        } else {
+            entity_mut.insert(Locked);
+        }
+    }
+}
+
+fn start_rename(world: &mut World, entity: Entity) {
+    let name = world
+        .get::<Name>(entity)
+        .map(|name| name.to_string())
+        .unwrap_or_default();
+
+    world.resource_mut::<OutlinerState>().renaming = Some((entity, name));
+}
+
+/// An inline text field that writes [`Name`] when it loses focus
+fn rename(ui: &mut egui::Ui, world: &mut World, entity: Entity) {
+    let mut state = world.resource_mut::<OutlinerState>();
+    let Some((_, name)) = state.renaming.as_mut() else {
+        return;
+    };
+
+    let response = ui.text_edit_singleline(name);
+    if !response.lost_focus() {
+        response.request_focus();
+        return;
+    }
+
+    let name = std::mem::take(name);
+    state.renaming = None;
+
+    if ui.input(|input| input.key_pressed(egui::Key::Escape)) {
+        return;
+    }
+
+    world.entity_mut(entity).insert(Name::new(name));
+}
+
+fn context_menu(ui: &mut egui::Ui, world: &mut World, entity: Entity) {
+    // Actions apply to the selection if the entity is selected, otherwise only to the entity
+    let select = |world: &mut World| {
+        let mut selected = world.resource_mut::<SelectedEntities>();
+        if !selected.0.contains(&entity) {
+            selected.0.clear();
+            selected.0.insert(entity);
+        }
+    };
+
+    if ui.button("Rename").clicked() {
+        start_rename(world, entity);
+        ui.close_menu();
+    }
+
+    if ui.button("Focus").clicked() {
+        select(world);
+        world.trigger(FrameEntities::Selected);
+        ui.close_menu();
+    }
+
+    if ui.button("Select Children").clicked() {
+        select(world);
+
+        let mut stack = world
+            .resource::<SelectedEntities>()
+            .0
+            .iter()
+            .cloned()
+            .collect::<Vec<Entity>>();
+        let mut descendants = Vec::new();
+        while let Some(entity) = stack.pop() {
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children.iter());
+                descendants.extend(children.iter());
+            }
+        }
+
+        world
+            .resource_mut::<SelectedEntities>()
+            .0
+            .extend(descendants);
+        ui.close_menu();
+    }
+
+    ui.separator();
+
+    if ui.button("Duplicate").clicked() {
+        select(world);
+        world.trigger(DuplicateSelected);
+        ui.close_menu();
+    }
+
+    if ui.button("Delete").clicked() {
+        select(world);
+        world.trigger(DeleteSelected);
+        ui.close_menu();
+    }
+}
+
+/// Finds the entities matching `filter`, or `None` if there is no filter
+fn build_filter(world: &mut World, filter: &str) -> Option<Filter> {
+    let mut components = Vec::new();
+    let mut search = Vec::new();
+    for token in filter.split_whitespace() {
+        match token.strip_prefix("has:") {
+            Some(component) if !component.is_empty() => {
+                components.push(component.to_lowercase());
+            }
+            Some(_) => {}
+            None => search.push(token.to_lowercase()),
+        }
+    }
+
+    if components.is_empty() && search.is_empty() {
+        return None;
+    }
+    let search = search.join(" ");
+
+    let mut query = world.query_filtered::<(Entity, Option<&Name>), Without<EditorEntity>>();
+    let mut matches = HashSet::new();
+    for (entity, name) in query.iter(world) {
+        if is_hidden_entity(world.entity(entity)) {
+            continue;
+        }
+
+        if !search.is_empty() {
+            let name = name.map_or_else(|| entity.to_string(), |name| name.to_lowercase());
+
+            // Substring or fuzzy match, like quick commands
+            if !name.contains(&search) && strsim::jaro_winkler(&name, &search) < 0.85 {
+                continue;
+            }
+        }
+
+        if !components.is_empty() {
+            let names = world
+                .inspect_entity(entity)
+                .into_iter()
+                .map(|info| get_component_name(info.name()).to_lowercase())
+                .collect::<Vec<String>>();
+
+            if !components.iter().all(|c| names.contains(c)) {
+                continue;
+            }
+        }
+
+        matches.insert(entity);
+    }
+
+    let mut parent_query = world.query::<&Parent>();
+    let mut visible = matches.clone();
+    for &entity in matches.iter() {
+        let mut current = entity;
+        while let Ok(parent) = parent_query.get(world, current) {
+            current = parent.get();
+            if !visible.insert(current) {
+                break;
+            }
+        }
+    }
+
+    Some(Filter { matches, visible })
+}
+
 fn is_hidden_entity(entity: EntityRef<'_>) -> bool {
     entity.contains::<Window>()
         || entity.contains::<bevy_mod_picking::pointer::PointerId>()
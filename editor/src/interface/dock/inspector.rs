@@ -1,10 +1,14 @@
 use std::any::TypeId;
 
-use bevy::{ecs::component::ComponentInfo, prelude::*};
+use bevy::{ecs::component::ComponentInfo, prelude::*, reflect::TypeRegistry};
 use bevy_egui::egui;
 
 use crate::{interface::components::ComponentUis, SelectedEntities};
 
+/// A component value copied from the inspector
+#[derive(Resource, Default)]
+pub struct CopiedComponent(pub Option<(TypeId, Box<dyn Reflect>)>);
+
 pub fn show(world: &mut World, ui: &mut egui::Ui) {
     let selected_entities = world.resource::<SelectedEntities>();
 
@@ -22,13 +26,89 @@ pub fn show(world: &mut World, ui: &mut egui::Ui) {
             }
 
             let name = get_component_name(info.name());
-            ui.collapsing(name, |ui| {
+            let response = ui.collapsing(name, |ui| {
                 if let Some(f) = component_uis.0.get(info.type_id().as_ref().unwrap()) {
                     f(ui, world.entity_mut(entity));
                 } else {
                     ui.label("Editing not supported :(");
                 }
             });
+
+            if let Some(type_id) = info.type_id() {
+                response
+                    .header_response
+                    .context_menu(|ui| component_menu(ui, world, entity, type_id));
+            }
+        }
+    });
+}
+
+/// Copy and paste of reflected component values
+fn component_menu(ui: &mut egui::Ui, world: &mut World, entity: Entity, type_id: TypeId) {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(type_id) else {
+        ui.label("This component isn't reflected.");
+        return;
+    };
+
+    if ui.button("Copy").clicked() {
+        let value = reflect_component
+            .reflect(world.entity(entity))
+            .map(|value| (type_id, value.clone_value()));
+        world.resource_mut::<CopiedComponent>().0 = value;
+        ui.close_menu();
+    }
+
+    let copied_type = world
+        .resource::<CopiedComponent>()
+        .0
+        .as_ref()
+        .map(|(type_id, _)| *type_id);
+
+    if ui
+        .add_enabled(
+            copied_type == Some(type_id),
+            egui::Button::new("Paste Values"),
+        )
+        .clicked()
+    {
+        world.resource_scope::<CopiedComponent, _>(|world, copied| {
+            if let Some((_, value)) = &copied.0 {
+                reflect_component.apply(&mut world.entity_mut(entity), &**value);
+            }
+        });
+        ui.close_menu();
+    }
+
+    if ui
+        .add_enabled(
+            copied_type.is_some(),
+            egui::Button::new("Paste To Selection"),
+        )
+        .on_hover_text("Adds or overwrites the copied component on every selected entity")
+        .clicked()
+    {
+        paste_to_selection(world, &registry);
+        ui.close_menu();
+    }
+}
+
+fn paste_to_selection(world: &mut World, registry: &TypeRegistry) {
+    world.resource_scope::<CopiedComponent, _>(|world, copied| {
+        let Some((type_id, value)) = &copied.0 else {
+            return;
+        };
+        let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(*type_id) else {
+            return;
+        };
+
+        let selected = world.resource::<SelectedEntities>().0.clone();
+        for entity in selected {
+            if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                reflect_component.apply_or_insert(&mut entity_mut, &**value, registry);
+            }
         }
     });
 }
@@ -47,7 +127,7 @@ fn is_hidden_component(id: Option<TypeId>) -> bool {
         || TypeId::of::<bevy_mod_picking::focus::PickingInteraction>() == id
 }
 
-fn get_component_name(name: &str) -> String {
+pub fn get_component_name(name: &str) -> String {
     let mut output = String::new();
     let mut queue = String::new();
     for c in name.chars() {
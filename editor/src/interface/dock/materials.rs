@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::{
+    interface::components::material_ui,
+    material::{
+        assign_material, duplicate_material, material_users, new_material, remove_material,
+        MaterialLibrary,
+    },
+    EditorEntity, SelectedEntities,
+};
+
+#[derive(Resource, Default)]
+pub struct MaterialsState {
+    pub filter: String,
+    /// The material shown below the list
+    pub selected: Option<AssetId<StandardMaterial>>,
+}
+
+enum MaterialAction {
+    New,
+    Duplicate(AssetId<StandardMaterial>),
+    Assign(AssetId<StandardMaterial>),
+    SelectUsers(AssetId<StandardMaterial>),
+    Remove(AssetId<StandardMaterial>),
+}
+
+pub fn show(world: &mut World, ui: &mut egui::Ui) {
+    let users = material_users(world);
+    let mut action = None;
+
+    world.resource_scope::<MaterialsState, _>(|world, mut state| {
+        ui.horizontal(|ui| {
+            if ui.button("New").clicked() {
+                action = Some(MaterialAction::New);
+            }
+            ui.add(egui::TextEdit::singleline(&mut state.filter).hint_text("Filter"));
+        });
+
+        ui.separator();
+
+        let filter = state.filter.to_lowercase();
+        egui::ScrollArea::vertical()
+            .id_source("Material List")
+            .max_height(ui.available_height() * 0.4)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                let library = world.resource::<MaterialLibrary>();
+                let materials = world.resource::<Assets<StandardMaterial>>();
+
+                for (id, name) in library.sorted() {
+                    if !name.to_lowercase().contains(&filter) {
+                        continue;
+                    }
+
+                    ui.horizontal(|ui| {
+                        swatch(ui, materials.get(id));
+
+                        let response = ui.selectable_label(state.selected == Some(id), name);
+                        if response.clicked() {
+                            state.selected = Some(id);
+                        }
+                        if response.double_clicked() {
+                            action = Some(MaterialAction::Assign(id));
+                        }
+
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.weak(users.get(&id).copied().unwrap_or(0).to_string())
+                                .on_hover_text("Entities using this material");
+                        });
+                    });
+                }
+            });
+
+        ui.separator();
+
+        let Some(id) = state
+            .selected
+            .filter(|id| world.resource::<MaterialLibrary>().0.contains_key(id))
+        else {
+            ui.label("Select a material to edit it.");
+            return;
+        };
+
+        let users = users.get(&id).copied().unwrap_or(0);
+
+        egui::ScrollArea::vertical()
+            .id_source("Material Properties")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name");
+                    let mut library = world.resource_mut::<MaterialLibrary>();
+                    if let Some(material) = library.0.get_mut(&id) {
+                        ui.text_edit_singleline(&mut material.name);
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Assign To Selection").clicked() {
+                        action = Some(MaterialAction::Assign(id));
+                    }
+                    if ui.button("Duplicate").clicked() {
+                        action = Some(MaterialAction::Duplicate(id));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(users > 0, egui::Button::new("Select Users"))
+                        .clicked()
+                    {
+                        action = Some(MaterialAction::SelectUsers(id));
+                    }
+                    if ui
+                        .add_enabled(users == 0, egui::Button::new("Remove"))
+                        .on_disabled_hover_text("Only unused materials can be removed")
+                        .clicked()
+                    {
+                        action = Some(MaterialAction::Remove(id));
+                    }
+                });
+
+                match users {
+                    0 => ui.label("Not used by any entity"),
+                    1 => ui.label("Used by 1 entity"),
+                    _ => ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("Shared by {users} entities"),
+                    ),
+                };
+
+                ui.separator();
+
+                let asset_server = world.resource::<AssetServer>().clone();
+                let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+                if let Some(material) = materials.get_mut(id) {
+                    material_ui(ui, material, &asset_server);
+                }
+            });
+    });
+
+    let Some(action) = action else {
+        return;
+    };
+
+    match action {
+        MaterialAction::New => {
+            let handle = new_material(world);
+            world.resource_mut::<MaterialsState>().selected = Some(handle.id());
+        }
+        MaterialAction::Duplicate(id) => {
+            if let Some(handle) = duplicate_material(world, id) {
+                world.resource_mut::<MaterialsState>().selected = Some(handle.id());
+            }
+        }
+        MaterialAction::Assign(id) => {
+            assign_material(world, id);
+        }
+        MaterialAction::SelectUsers(id) => {
+            let entities = world
+                .query_filtered::<(Entity, &Handle<StandardMaterial>), Without<EditorEntity>>()
+                .iter(world)
+                .filter(|(_, handle)| handle.id() == id)
+                .map(|(entity, _)| entity)
+                .collect();
+            world.resource_mut::<SelectedEntities>().0 = entities;
+        }
+        MaterialAction::Remove(id) => {
+            remove_material(world, id);
+            world.resource_mut::<MaterialsState>().selected = None;
+        }
+    }
+}
+
+/// A small square of the material's base color
+fn swatch(ui: &mut egui::Ui, material: Option<&StandardMaterial>) {
+    let color = material
+        .map(|material| material.base_color.to_srgba().to_u8_array())
+        .map(|[r, g, b, a]| egui::Color32::from_rgba_unmultiplied(r, g, b, a))
+        .unwrap_or(egui::Color32::TRANSPARENT);
+
+    let (rect, _response) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+    ui.painter().rect_filled(rect, 2.0, color);
+}
@@ -8,13 +8,94 @@ use bevy::{
     pbr::{ScreenSpaceAmbientOcclusionSettings, ScreenSpaceReflectionsSettings},
     prelude::*,
 };
-use bevy_egui::egui;
+use bevy_egui::egui::{self, ComboBox};
 
-use crate::{camera::Flycam, EditorEntity};
+use crate::{
+    camera::{
+        set_active_camera, ActiveCamera, AlignView, BookmarkKey, CameraBookmarks, CameraMode,
+        Flycam, RecallBookmark, StoreBookmark, ViewDirection,
+    },
+    EditorEntity,
+};
+
+/// Draws the world axes as seen by `camera` in the corner of its viewport,
+/// clicking an axis aligns the view to it. Returns true if the cursor is over the widget.
+pub fn orientation(world: &mut World, ui: &mut egui::Ui, camera: Entity) -> bool {
+    let Some(transform) = world.get::<Transform>(camera) else {
+        return false;
+    };
+    let inverse_rotation = transform.rotation.inverse();
+
+    let radius = 36.0;
+    let center = ui.clip_rect().right_top() + egui::vec2(-radius - 20.0, radius + 20.0);
+    let rect = egui::Rect::from_center_size(center, egui::Vec2::splat(radius * 2.0 + 20.0));
+    let hovered = ui.rect_contains_pointer(rect);
+    let painter = ui.painter();
+
+    // Draw back to front so the closest axis is on top
+    let mut axes = ViewDirection::ALL
+        .into_iter()
+        .map(|direction| (direction, inverse_rotation * direction.offset()))
+        .collect::<Vec<(ViewDirection, Vec3)>>();
+    axes.sort_by(|a, b| a.1.z.total_cmp(&b.1.z));
+
+    if hovered {
+        painter.circle_filled(center, radius + 10.0, egui::Color32::from_white_alpha(16));
+    }
+
+    let mut clicked = None;
+    for (direction, view) in axes {
+        let position = center + egui::vec2(view.x, -view.y) * radius;
+        let offset = direction.offset().abs();
+        let color = egui::Color32::from_rgb(
+            (offset.x * 220.0) as u8 + 35,
+            (offset.y * 180.0) as u8 + 35,
+            (offset.z * 220.0) as u8 + 35,
+        );
+        let is_positive = direction.offset().max_element() > 0.0;
+
+        let response = ui.interact(
+            egui::Rect::from_center_size(position, egui::Vec2::splat(18.0)),
+            ui.id().with(("orientation", direction)),
+            egui::Sense::click(),
+        );
+
+        let fill = if response.hovered() {
+            egui::Color32::WHITE
+        } else if is_positive {
+            color
+        } else {
+            color.gamma_multiply(0.5)
+        };
+
+        if is_positive {
+            painter.line_segment([center, position], egui::Stroke::new(2.0, color));
+        }
+        painter.circle_filled(position, 9.0, fill);
+        painter.text(
+            position,
+            egui::Align2::CENTER_CENTER,
+            direction.label(),
+            egui::FontId::proportional(10.0),
+            egui::Color32::BLACK,
+        );
+
+        if response.clicked() {
+            clicked = Some(direction);
+        }
+    }
+
+    if let Some(direction) = clicked {
+        set_active_camera(world, camera);
+        world.trigger(AlignView(direction));
+    }
+
+    hovered
+}
 
 pub fn settings(world: &mut World, ui: &mut egui::Ui) {
     let Ok(camera) = world
-        .query_filtered::<Entity, (With<EditorEntity>, With<Flycam>)>()
+        .query_filtered::<Entity, (With<EditorEntity>, With<ActiveCamera>)>()
         .get_single(world)
     else {
         return;
@@ -22,6 +103,33 @@ pub fn settings(world: &mut World, ui: &mut egui::Ui) {
 
     let mut entity = world.get_entity_mut(camera).unwrap();
 
+    ui.heading("Camera");
+
+    let mut flycam = entity.get_mut::<Flycam>().unwrap();
+    ComboBox::from_label("Mode")
+        .selected_text(format!("{:?}", flycam.mode))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut flycam.mode, CameraMode::Fly, "Fly");
+            ui.selectable_value(&mut flycam.mode, CameraMode::Orbit, "Orbit");
+        });
+
+    if let Some(view) = flycam.view {
+        ui.label(format!("{view:?} View"));
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Speed");
+        ui.add(
+            egui::DragValue::new(&mut flycam.speed)
+                .range(0.1..=1000.0)
+                .speed(0.1),
+        );
+    });
+
+    if flycam.mode == CameraMode::Orbit {
+        ui.checkbox(&mut flycam.orbit_selection, "Orbit Selection");
+    }
+
     ui.heading("Graphics");
 
     let mut has_bloom = entity.contains::<BloomSettings>();
@@ -91,4 +199,74 @@ pub fn settings(world: &mut World, ui: &mut egui::Ui) {
     if !has_deferred && (has_ssr) {
         entity.insert(DeferredPrepass);
     }
+
+    ui.heading("Bookmarks");
+    bookmarks(world, ui);
+}
+
+fn bookmarks(world: &mut World, ui: &mut egui::Ui) {
+    let mut bookmarks = world.resource_mut::<CameraBookmarks>();
+
+    let mut recall = None;
+    let mut store = None;
+    let mut remove = None;
+    let mut slotted = None;
+    for (i, bookmark) in bookmarks.0.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut bookmark.name).desired_width(100.0));
+
+            // Slots stay with their bookmark when others are removed
+            let slot_text =
+                |slot: Option<u8>| slot.map_or("-".to_string(), |slot| slot.to_string());
+            let previous = bookmark.slot;
+            ComboBox::from_id_source(("Bookmark Slot", i))
+                .width(32.0)
+                .selected_text(slot_text(bookmark.slot))
+                .show_ui(ui, |ui| {
+                    for slot in std::iter::once(None).chain((1..=9).map(Some)) {
+                        ui.selectable_value(&mut bookmark.slot, slot, slot_text(slot));
+                    }
+                })
+                .response
+                .on_hover_text("Ctrl + the slot recalls the bookmark");
+            if bookmark.slot != previous {
+                slotted = Some(i);
+            }
+
+            if ui.button("Go").clicked() {
+                recall = Some(BookmarkKey::Index(i));
+            }
+            if ui.button("Update").clicked() {
+                store = Some(Some(BookmarkKey::Index(i)));
+            }
+            if ui.button("Remove").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+
+    // A slot belongs to one bookmark
+    if let Some(i) = slotted {
+        let slot = bookmarks.0[i].slot;
+        for (other, bookmark) in bookmarks.0.iter_mut().enumerate() {
+            if other != i && slot.is_some() && bookmark.slot == slot {
+                bookmark.slot = None;
+            }
+        }
+    }
+
+    if let Some(i) = remove {
+        bookmarks.0.remove(i);
+    }
+
+    if ui.button("Add Bookmark").clicked() {
+        store = Some(None);
+    }
+
+    if let Some(key) = recall {
+        world.trigger(RecallBookmark(key));
+    }
+    if let Some(key) = store {
+        world.trigger(StoreBookmark(key));
+    }
 }
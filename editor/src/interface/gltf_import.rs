@@ -0,0 +1,359 @@
+use std::{f32::consts::FRAC_PI_2, path::PathBuf};
+
+use bevy::{
+    asset::LoadState,
+    gltf::{Gltf, GltfMesh},
+    prelude::*,
+    utils::HashSet,
+};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::{command::add::SpawnPoint, SelectedEntities};
+
+use super::InterfaceSet;
+
+#[derive(Clone, PartialEq)]
+pub enum GltfSelection {
+    /// A scene by index
+    Scene(usize),
+    /// Meshes by index, placed under one entity
+    Meshes(HashSet<usize>),
+}
+
+/// The options of the glTF import popup, the popup is shown while this exists
+#[derive(Resource, Clone)]
+pub struct GltfImport {
+    pub path: PathBuf,
+    pub gltf: Handle<Gltf>,
+    /// `None` until the file is loaded
+    pub selection: Option<GltfSelection>,
+    pub scale: f32,
+    /// Rotates files authored with Z up to Y up
+    pub z_up: bool,
+    /// Spawns the scene as editable entities instead of a reference to the file
+    pub unpack: bool,
+    /// Where a file dropped on a viewport is placed, instead of the spawn point
+    pub translation: Option<Vec3>,
+}
+
+impl GltfImport {
+    pub fn new(path: PathBuf, gltf: Handle<Gltf>) -> Self {
+        Self {
+            path,
+            gltf,
+            selection: None,
+            scale: 1.0,
+            z_up: false,
+            unpack: false,
+            translation: None,
+        }
+    }
+
+    fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "glTF".to_string())
+    }
+}
+
+/// Spawns the selected part of the file and selects it
+fn apply(world: &mut World, import: GltfImport, translation: Vec3) {
+    let Some(selection) = import.selection.clone() else {
+        return;
+    };
+
+    let transform = Transform {
+        translation,
+        rotation: if import.z_up {
+            Quat::from_rotation_x(-FRAC_PI_2)
+        } else {
+            Quat::IDENTITY
+        },
+        scale: Vec3::splat(import.scale),
+    };
+
+    let Some(gltf) = world.resource::<Assets<Gltf>>().get(&import.gltf) else {
+        error!("The glTF file isn't loaded: {:?}", import.path);
+        return;
+    };
+
+    let root = match selection {
+        GltfSelection::Scene(index) if !import.unpack => {
+            let scene = gltf.scenes[index].clone();
+            world
+                .spawn((
+                    SceneBundle {
+                        scene,
+                        transform,
+                        ..default()
+                    },
+                    Name::new(import.name()),
+                ))
+                .id()
+        }
+        GltfSelection::Scene(index) => {
+            let scene = gltf.scenes[index].clone();
+            let root = world
+                .spawn((
+                    SpatialBundle::from_transform(transform),
+                    Name::new(import.name()),
+                ))
+                .id();
+
+            let registry = world.resource::<AppTypeRegistry>().clone();
+            let result = world.resource_scope::<Assets<Scene>, _>(|world, scenes| {
+                scenes
+                    .get(&scene)
+                    .map(|scene| scene.write_to_world_with(world, &registry))
+            });
+
+            match result {
+                Some(Ok(instance)) => {
+                    for &entity in instance.entity_map.values() {
+                        if world.get::<Parent>(entity).is_none() {
+                            world.entity_mut(root).add_child(entity);
+                        }
+                    }
+                }
+                Some(Err(e)) => error!("Failed to unpack glTF scene: {:?}\n{e:?}", import.path),
+                None => error!("The glTF scene isn't loaded: {:?}", import.path),
+            }
+
+            root
+        }
+        GltfSelection::Meshes(indices) => {
+            let mut indices = indices.into_iter().collect::<Vec<usize>>();
+            indices.sort();
+
+            let gltf_meshes = world.resource::<Assets<GltfMesh>>();
+            let meshes = indices
+                .into_iter()
+                .filter_map(|index| gltf_meshes.get(&gltf.meshes[index]))
+                .map(|mesh| (mesh.name.clone(), mesh.primitives.clone()))
+                .collect::<Vec<_>>();
+
+            let root = world
+                .spawn((
+                    SpatialBundle::from_transform(transform),
+                    Name::new(import.name()),
+                ))
+                .id();
+
+            for (name, primitives) in meshes {
+                let mesh = world
+                    .spawn((SpatialBundle::default(), Name::new(name)))
+                    .set_parent(root)
+                    .id();
+
+                for primitive in primitives {
+                    world
+                        .spawn((
+                            PbrBundle {
+                                mesh: primitive.mesh,
+                                material: primitive.material.unwrap_or_default(),
+                                ..default()
+                            },
+                            Name::new(primitive.name),
+                        ))
+                        .set_parent(mesh);
+                }
+            }
+
+            root
+        }
+    };
+
+    let mut selected = world.resource_mut::<SelectedEntities>();
+    selected.0.clear();
+    selected.0.insert(root);
+}
+
+fn show(
+    mut import: ResMut<GltfImport>,
+    gltfs: Res<Assets<Gltf>>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+    asset_server: Res<AssetServer>,
+    spawn_point: SpawnPoint,
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+) {
+    let ctx = contexts.ctx_mut();
+    let gltf = gltfs.get(&import.gltf);
+
+    // Starts with the default scene, or the meshes for files without scenes
+    if let (Some(gltf), true) = (gltf, import.selection.is_none()) {
+        let scene = gltf
+            .default_scene
+            .as_ref()
+            .and_then(|default| gltf.scenes.iter().position(|scene| scene == default))
+            .unwrap_or(0);
+
+        import.selection = Some(if gltf.scenes.is_empty() {
+            GltfSelection::Meshes(HashSet::new())
+        } else {
+            GltfSelection::Scene(scene)
+        });
+    }
+
+    let mut open = true;
+    let mut import_clicked = false;
+    let mut cancel_clicked = false;
+    egui::Window::new("Import glTF")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(import.path.to_string_lossy());
+            ui.separator();
+
+            let Some(gltf) = gltf else {
+                if matches!(asset_server.load_state(&import.gltf), LoadState::Failed(_)) {
+                    ui.label("Failed to load the file.");
+                } else {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Loading...");
+                    });
+                }
+
+                cancel_clicked = ui.button("Cancel").clicked();
+                return;
+            };
+
+            let import = &mut *import;
+            let selection = import.selection.get_or_insert(GltfSelection::Scene(0));
+
+            ui.label("Scenes");
+            for (index, scene) in gltf.scenes.iter().enumerate() {
+                let name = gltf
+                    .named_scenes
+                    .iter()
+                    .find(|(_, named)| *named == scene)
+                    .map(|(name, _)| name.to_string())
+                    .unwrap_or_else(|| format!("Scene {index}"));
+
+                if ui
+                    .radio(*selection == GltfSelection::Scene(index), name)
+                    .clicked()
+                {
+                    *selection = GltfSelection::Scene(index);
+                }
+            }
+
+            let is_meshes = matches!(selection, GltfSelection::Meshes(_));
+            if ui.radio(is_meshes, "Individual Meshes").clicked() && !is_meshes {
+                *selection = GltfSelection::Meshes(HashSet::new());
+            }
+
+            if let GltfSelection::Meshes(indices) = selection {
+                egui::ScrollArea::vertical()
+                    .id_source("glTF Meshes")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        ui.indent("glTF Meshes", |ui| {
+                            for (index, mesh) in gltf.meshes.iter().enumerate() {
+                                let name = gltf_meshes
+                                    .get(mesh)
+                                    .map(|mesh| mesh.name.clone())
+                                    .unwrap_or_else(|| format!("Mesh {index}"));
+
+                                let mut checked = indices.contains(&index);
+                                if ui.checkbox(&mut checked, name).changed() {
+                                    if checked {
+                                        indices.insert(index);
+                                    } else {
+                                        indices.remove(&index);
+                                    }
+                                }
+                            }
+                        });
+                    });
+            }
+
+            ui.separator();
+
+            names_ui(ui, "Nodes", gltf.named_nodes.keys().map(AsRef::as_ref));
+            names_ui(
+                ui,
+                "Animations",
+                gltf.named_animations.keys().map(AsRef::as_ref),
+            );
+
+            ui.separator();
+
+            let is_meshes = matches!(selection, GltfSelection::Meshes(_));
+            egui::Grid::new("glTF Import Options")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Scale");
+                    ui.add(
+                        egui::DragValue::new(&mut import.scale)
+                            .range(0.0001..=f32::MAX)
+                            .speed(0.01),
+                    );
+                    ui.end_row();
+
+                    ui.label("Z Up");
+                    ui.checkbox(&mut import.z_up, "")
+                        .on_hover_text("Rotates files authored with Z up to Y up");
+                    ui.end_row();
+
+                    ui.label("Unpack");
+                    ui.add_enabled(!is_meshes, egui::Checkbox::without_text(&mut import.unpack))
+                        .on_hover_text(
+                            "Spawns editable entities instead of a reference to the file's scene",
+                        );
+                    ui.end_row();
+                });
+
+            ui.separator();
+
+            let can_import = match &import.selection {
+                Some(GltfSelection::Meshes(indices)) => !indices.is_empty(),
+                Some(GltfSelection::Scene(_)) => true,
+                None => false,
+            };
+            ui.horizontal(|ui| {
+                import_clicked = ui
+                    .add_enabled(can_import, egui::Button::new("Import"))
+                    .clicked();
+                cancel_clicked = ui.button("Cancel").clicked();
+            });
+        });
+
+    if import_clicked {
+        let import = import.clone();
+        let translation = import
+            .translation
+            .unwrap_or_else(|| spawn_point.translation());
+        commands.add(move |world: &mut World| apply(world, import, translation));
+    }
+
+    if !open || import_clicked || cancel_clicked {
+        commands.remove_resource::<GltfImport>();
+    }
+}
+
+/// A collapsed, sorted list of names
+fn names_ui<'a>(ui: &mut egui::Ui, label: &str, names: impl Iterator<Item = &'a str>) {
+    let mut names = names.collect::<Vec<_>>();
+    names.sort();
+
+    ui.collapsing(format!("{label} ({})", names.len()), |ui| {
+        for name in names {
+            ui.label(name);
+        }
+    });
+}
+
+pub struct GltfImportPlugin;
+impl Plugin for GltfImportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            show.in_set(InterfaceSet::Overlay)
+                .run_if(resource_exists::<GltfImport>),
+        );
+    }
+}
@@ -1,15 +1,28 @@
+pub mod array;
 mod components;
 mod dock;
+pub mod gltf_import;
 pub mod quick;
 mod toolbar;
 
-use bevy::{prelude::*, render::camera::Viewport, window::PrimaryWindow};
+use std::time::Duration;
+
+use bevy::{
+    prelude::*, render::camera::Viewport, time::common_conditions::on_timer, utils::HashMap,
+    window::PrimaryWindow,
+};
 use bevy_egui::{egui, EguiContext, EguiPlugin, EguiSet};
 use components::ComponentUis;
-use dock::{InterfaceTab, InterfaceTabViewer};
+use dock::{
+    scan_assets, AssetBrowser, CopiedComponent, InterfaceTab, InterfaceTabViewer, MaterialsState,
+    OutlinerState,
+};
 use egui_dock::{DockArea, DockState, NodeIndex};
 
-use crate::{camera::Flycam, EditorAction, EditorEntity};
+use crate::{
+    camera::{editor_camera, ActiveCamera, Flycam},
+    EditorAction, EditorEntity,
+};
 
 #[derive(SystemSet, PartialEq, Eq, Hash, Clone, Debug)]
 pub enum InterfaceSet {
@@ -22,7 +35,8 @@ pub enum InterfaceSet {
 #[derive(Resource)]
 pub struct InterfaceState {
     pub dock_state: DockState<InterfaceTab>,
-    pub viewport_rect: egui::Rect,
+    /// The rects of the viewports shown this frame, by camera
+    pub viewport_rects: HashMap<Entity, egui::Rect>,
     pub cursor_over_ui: bool,
 }
 
@@ -35,6 +49,9 @@ impl InterfaceState {
         });
 
         let mut added_tabs = Vec::new();
+        self.viewport_rects.clear();
+        self.cursor_over_ui = true;
+
         let mut style = egui_dock::Style::from_egui(ctx.style().as_ref());
         style.buttons.add_tab_align = egui_dock::TabAddAlign::Left;
 
@@ -46,7 +63,7 @@ impl InterfaceState {
                 ctx,
                 &mut InterfaceTabViewer {
                     world,
-                    viewport_rect: &mut self.viewport_rect,
+                    viewport_rects: &mut self.viewport_rects,
                     cursor_over_ui: &mut self.cursor_over_ui,
                     added_tabs: &mut added_tabs,
                 },
@@ -60,26 +77,47 @@ impl InterfaceState {
     }
 }
 
-impl Default for InterfaceState {
-    fn default() -> Self {
-        let mut dock_state =
-            DockState::new(vec![InterfaceTab::Viewport, InterfaceTab::ViewSettings]);
+impl InterfaceState {
+    /// The default layout with `camera` in the viewport
+    pub fn new(camera: Entity) -> Self {
+        let mut dock_state = DockState::new(vec![
+            InterfaceTab::Viewport(camera),
+            InterfaceTab::ViewSettings,
+        ]);
         let surface = dock_state.main_surface_mut();
 
-        let [_viewport, entities] =
+        let [viewport, entities] =
             surface.split_left(NodeIndex::root(), 0.2, vec![InterfaceTab::Entities]);
+        surface.split_below(viewport, 0.75, vec![InterfaceTab::Assets]);
 
-        let [_entities, _inspector] =
-            surface.split_below(entities, 0.5, vec![InterfaceTab::Inspector]);
+        let [_entities, _inspector] = surface.split_below(
+            entities,
+            0.5,
+            vec![InterfaceTab::Inspector, InterfaceTab::Materials],
+        );
 
         Self {
             dock_state,
-            viewport_rect: egui::Rect::NOTHING,
+            viewport_rects: HashMap::new(),
             cursor_over_ui: false,
         }
     }
 }
 
+fn setup(mut commands: Commands) {
+    let camera = commands
+        .spawn((
+            editor_camera(
+                0,
+                Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ),
+            ActiveCamera,
+        ))
+        .id();
+
+    commands.insert_resource(InterfaceState::new(camera));
+}
+
 // https://github.com/jakobhellermann/bevy-inspector-egui/blob/main/crates/bevy-inspector-egui/examples/integrations/egui_dock.rs#L82C4-L82C18
 fn show_ui(world: &mut World) {
     let Ok(egui_context) = world
@@ -108,30 +146,43 @@ fn set_camera_viewport(
     state: Res<InterfaceState>,
     egui_settings: Res<bevy_egui::EguiSettings>,
     primary_window: Query<&Window, With<PrimaryWindow>>,
-    mut camera_query: Query<&mut Camera, (With<Flycam>, With<EditorEntity>)>,
+    mut camera_query: Query<(Entity, &mut Camera), (With<Flycam>, With<EditorEntity>)>,
 ) {
-    let mut camera = camera_query.single_mut();
     let Ok(window) = primary_window.get_single() else {
         return;
     };
 
     let scale_factor = window.scale_factor() * egui_settings.scale_factor;
 
-    let viewport_pos = state.viewport_rect.left_top().to_vec2() * scale_factor;
-    let viewport_size = state.viewport_rect.size() * scale_factor;
+    for (entity, mut camera) in camera_query.iter_mut() {
+        // Cameras of hidden viewport tabs don't need to render
+        let Some(viewport_rect) = state.viewport_rects.get(&entity) else {
+            if camera.is_active {
+                camera.is_active = false;
+            }
+            continue;
+        };
+
+        if !camera.is_active {
+            camera.is_active = true;
+        }
+
+        let viewport_pos = viewport_rect.left_top().to_vec2() * scale_factor;
+        let viewport_size = viewport_rect.size() * scale_factor;
 
-    let physical_position = UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32);
-    let physical_size = UVec2::new(viewport_size.x as u32, viewport_size.y as u32);
+        let physical_position = UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32);
+        let physical_size = UVec2::new(viewport_size.x as u32, viewport_size.y as u32);
 
-    let window_size = window.physical_size();
-    let far_corner = physical_position + physical_size;
+        let window_size = window.physical_size();
+        let far_corner = physical_position + physical_size;
 
-    if far_corner.x <= window_size.x && far_corner.y <= window_size.y {
-        camera.viewport = Some(Viewport {
-            physical_position,
-            physical_size,
-            depth: 0.0..1.0,
-        });
+        if far_corner.x <= window_size.x && far_corner.y <= window_size.y {
+            camera.viewport = Some(Viewport {
+                physical_position,
+                physical_size,
+                depth: 0.0..1.0,
+            });
+        }
     }
 }
 
@@ -139,8 +190,11 @@ pub struct InterfacePlugin;
 impl Plugin for InterfacePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(EguiPlugin);
-        app.insert_resource(InterfaceState::default());
         app.insert_resource(ComponentUis::default());
+        app.insert_resource(OutlinerState::default());
+        app.insert_resource(CopiedComponent::default());
+        app.insert_resource(MaterialsState::default());
+        app.insert_resource(AssetBrowser::default());
 
         app.configure_sets(
             PostUpdate,
@@ -155,7 +209,7 @@ impl Plugin for InterfacePlugin {
                 .before(TransformSystem::TransformPropagate),
         );
 
-        app.add_systems(PreStartup, components::setup);
+        app.add_systems(PreStartup, (setup, components::setup));
         app.add_systems(
             PostUpdate,
             (
@@ -163,7 +217,10 @@ impl Plugin for InterfacePlugin {
                 set_camera_viewport.in_set(InterfaceSet::Post),
             ),
         );
+        app.add_systems(Update, scan_assets.run_if(on_timer(Duration::from_secs(1))));
 
         app.add_plugins(quick::QuickCommandPlugin);
+        app.add_plugins(array::ArrayToolPlugin);
+        app.add_plugins(gltf_import::GltfImportPlugin);
     }
 }
@@ -1,22 +1,42 @@
 mod camera;
 mod command;
+mod gltf_export;
 mod interface;
+mod material;
+mod mesh_file;
 mod observers;
 mod picking;
+mod prefab;
+mod primitive;
 mod transform;
+mod vrm;
 
-use bevy::{color::palettes::css::GOLD, prelude::*, render::primitives::Aabb, utils::HashSet};
-use camera::{Flycam, FlycamPlugin};
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::io::file::FileAssetReader,
+    color::palettes::css::{GOLD, GRAY},
+    prelude::*,
+    render::primitives::Aabb,
+    utils::HashSet,
+};
+use camera::{ActiveCamera, Flycam, FlycamPlugin, LookThrough, ViewDirection};
 use command::CommandPlugin;
 use indexmap::IndexSet;
 use interface::InterfacePlugin;
+use material::MaterialLibraryPlugin;
+use mesh_file::MeshFilePlugin;
 use observers::ObserverPlugin;
 use picking::PickingPlugin;
+use prefab::PrefabPlugin;
+use primitive::PrimitivePlugin;
+use vrm::VrmPlugin;
 
 pub const GUI_ACTION_ID: u64 = 0;
 pub const CAMERA_ACTION_ID: u64 = 1;
 pub const TRANSFORM_ACTION_ID: u64 = 2;
 pub const QUICK_COMMANDS_ACTION_ID: u64 = 3;
+pub const ORBIT_ACTION_ID: u64 = 4;
 
 #[derive(Resource, Deref)]
 pub struct EditorAction(pub Option<u64>);
@@ -24,14 +44,30 @@ pub struct EditorAction(pub Option<u64>);
 #[derive(Component)]
 pub struct EditorEntity;
 
+/// Editor only marker for entities that can't be picked or transformed
+#[derive(Component)]
+pub struct Locked;
+
 #[derive(Resource)]
 pub struct SelectedEntities(pub IndexSet<Entity>);
 
+/// An editor action that can be undone
+pub struct UndoRecord {
+    pub name: String,
+    pub undo: Box<dyn FnOnce(&mut World) + Send + Sync>,
+}
+
+/// The actions that can be undone, the last one is undone first.
+/// Only actions that push a record here can be undone
+#[derive(Resource, Default)]
+pub struct UndoStack(pub Vec<UndoRecord>);
+
 fn main() {
     let mut app = App::new();
 
     app.insert_resource(SelectedEntities(IndexSet::default()));
     app.insert_resource(EditorAction(None));
+    app.init_resource::<UndoStack>();
 
     app.add_plugins((
         DefaultPlugins.set(WindowPlugin {
@@ -47,30 +83,22 @@ fn main() {
         PickingPlugin,
         transform::TransformPlugin,
         CommandPlugin,
+        PrefabPlugin,
+        PrimitivePlugin,
+        MaterialLibraryPlugin,
+        VrmPlugin,
+        MeshFilePlugin,
     ));
     app.add_systems(Startup, setup);
     app.add_systems(PreUpdate, keybindings);
-    app.add_systems(PostUpdate, (grid, selection_outlines));
+    app.add_systems(PostUpdate, (grid, selection_outlines, camera_frustums));
     app.run();
 }
 
-fn setup(mut commands: Commands, mut gizmo_config_store: ResMut<GizmoConfigStore>) {
+fn setup(mut gizmo_config_store: ResMut<GizmoConfigStore>) {
     let (_gizmo_config, gizmo_light_config) =
         gizmo_config_store.config_mut::<LightGizmoConfigGroup>();
     gizmo_light_config.draw_all = true;
-
-    commands.spawn((
-        Camera3dBundle {
-            camera: Camera {
-                hdr: true,
-                ..default()
-            },
-            transform: Transform::from_xyz(5.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-            ..default()
-        },
-        Flycam::default(),
-        EditorEntity,
-    ));
 }
 
 fn keybindings(
@@ -92,6 +120,89 @@ fn keybindings(
     if keys.just_pressed(KeyCode::Delete) && editor_action.is_none() {
         commands.trigger(observers::DeleteSelected);
     }
+
+    if editor_action.is_none() && keys.pressed(KeyCode::ControlLeft) {
+        if keys.just_pressed(KeyCode::KeyC) {
+            commands.add(command::edit::copy);
+        } else if keys.just_pressed(KeyCode::KeyX) {
+            commands.add(command::edit::cut);
+        } else if keys.just_pressed(KeyCode::KeyV) {
+            commands.add(command::edit::paste);
+        } else if keys.just_pressed(KeyCode::KeyZ) {
+            commands.add(command::edit::undo);
+        }
+    }
+
+    if keys.pressed(KeyCode::ShiftLeft)
+        && keys.just_pressed(KeyCode::KeyD)
+        && editor_action.is_none()
+    {
+        commands.trigger(observers::DuplicateSelected);
+    }
+
+    if keys.just_pressed(KeyCode::KeyF) && editor_action.is_none() {
+        commands.trigger(camera::FrameEntities::Selected);
+    } else if keys.just_pressed(KeyCode::Home) && editor_action.is_none() {
+        commands.trigger(camera::FrameEntities::All);
+    }
+
+    // Numpad views, control views from the opposite side
+    if editor_action.is_none() {
+        let opposite = keys.pressed(KeyCode::ControlLeft);
+        let direction = if keys.just_pressed(KeyCode::Numpad7) {
+            Some(if opposite {
+                ViewDirection::Bottom
+            } else {
+                ViewDirection::Top
+            })
+        } else if keys.just_pressed(KeyCode::Numpad1) {
+            Some(if opposite {
+                ViewDirection::Back
+            } else {
+                ViewDirection::Front
+            })
+        } else if keys.just_pressed(KeyCode::Numpad3) {
+            Some(if opposite {
+                ViewDirection::Left
+            } else {
+                ViewDirection::Right
+            })
+        } else {
+            None
+        };
+
+        if let Some(direction) = direction {
+            commands.trigger(camera::AlignView(direction));
+        } else if keys.just_pressed(KeyCode::Numpad5) {
+            commands.trigger(camera::ToggleOrthographic);
+        } else if keys.just_pressed(KeyCode::Numpad0) {
+            commands.trigger(camera::LookThroughSelected);
+        }
+    }
+
+    // Camera bookmarks, Ctrl + 1..9 to recall and Ctrl + Shift + 1..9 to store
+    if editor_action.is_none() && keys.pressed(KeyCode::ControlLeft) {
+        const DIGITS: [KeyCode; 9] = [
+            KeyCode::Digit1,
+            KeyCode::Digit2,
+            KeyCode::Digit3,
+            KeyCode::Digit4,
+            KeyCode::Digit5,
+            KeyCode::Digit6,
+            KeyCode::Digit7,
+            KeyCode::Digit8,
+            KeyCode::Digit9,
+        ];
+
+        if let Some(index) = DIGITS.iter().position(|key| keys.just_pressed(*key)) {
+            let slot = camera::BookmarkKey::Slot(index as u8 + 1);
+            if keys.pressed(KeyCode::ShiftLeft) {
+                commands.trigger(camera::StoreBookmark(Some(slot)));
+            } else {
+                commands.trigger(camera::RecallBookmark(slot));
+            }
+        }
+    }
 }
 
 fn selection_outlines(
@@ -124,8 +235,97 @@ fn selection_outlines(
     }
 }
 
-fn grid(camera_query: Query<&Transform, With<Flycam>>, mut gizmos: Gizmos) {
-    let camera = camera_query.get_single().unwrap();
+fn camera_frustums(
+    query: Query<(Entity, &GlobalTransform, &Projection), (With<Camera>, Without<EditorEntity>)>,
+    look_through_query: Query<&LookThrough>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, global_transform, projection) in query.iter() {
+        if look_through_query.iter().any(|l| l.camera == entity) {
+            continue;
+        }
+
+        // Only show the first unit of the frustum, the far plane is usually too far away
+        let (near, far) = match projection {
+            Projection::Perspective(perspective) => {
+                let half_height = (perspective.fov / 2.0).tan();
+                let far = Vec2::new(half_height * perspective.aspect_ratio, half_height);
+                (Vec2::ZERO, far)
+            }
+            Projection::Orthographic(orthographic) => {
+                let half_size = orthographic.area.half_size();
+                (half_size, half_size)
+            }
+        };
+
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        let near_corners = corners
+            .map(|(x, y)| global_transform.transform_point(Vec3::new(x * near.x, y * near.y, 0.0)));
+        let far_corners = corners
+            .map(|(x, y)| global_transform.transform_point(Vec3::new(x * far.x, y * far.y, -1.0)));
+
+        for i in 0..4 {
+            gizmos.line(near_corners[i], far_corners[i], GRAY);
+            gizmos.line(far_corners[i], far_corners[(i + 1) % 4], GRAY);
+        }
+
+        // Up indicator
+        let up = global_transform.transform_point(Vec3::new(0.0, far.y * 1.5, -1.0));
+        gizmos.line(far_corners[2], up, GRAY);
+        gizmos.line(far_corners[3], up, GRAY);
+    }
+}
+
+/// The world space min and max corners of an [`Aabb`] after it's transformed
+pub fn world_bounds(aabb: &Aabb, global_transform: &GlobalTransform) -> (Vec3, Vec3) {
+    let mut min = Vec3::INFINITY;
+    let mut max = Vec3::NEG_INFINITY;
+    for i in 0..8 {
+        let corner = Vec3::new(
+            if i & 1 == 0 { -1.0 } else { 1.0 },
+            if i & 2 == 0 { -1.0 } else { 1.0 },
+            if i & 4 == 0 { -1.0 } else { 1.0 },
+        );
+        let point = global_transform
+            .transform_point(Vec3::from(aabb.center) + corner * Vec3::from(aabb.half_extents));
+
+        min = min.min(point);
+        max = max.max(point);
+    }
+
+    (min, max)
+}
+
+/// The `assets` folder the asset server loads from
+pub fn assets_folder() -> PathBuf {
+    FileAssetReader::get_base_path().join("assets")
+}
+
+/// `path` relative to the `assets` folder when it's inside it,
+/// so saved scenes keep working when the project is moved
+pub fn asset_path(path: &Path) -> String {
+    let root = assets_folder();
+    let canonical_root = std::fs::canonicalize(&root).unwrap_or_else(|_| root.clone());
+
+    path.strip_prefix(&root)
+        .or_else(|_| path.strip_prefix(&canonical_root))
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// The file an [`asset_path`] refers to
+pub fn asset_file(path: &str) -> PathBuf {
+    assets_folder().join(path)
+}
+
+fn grid(
+    camera_query: Query<(&Transform, &Flycam, &Projection), With<ActiveCamera>>,
+    mut gizmos: Gizmos,
+) {
+    let Ok((camera, flycam, projection)) = camera_query.get_single() else {
+        return;
+    };
 
     gizmos.line(
         Vec3::X * 1000.0 + Vec3::Y * 0.001,
@@ -168,8 +368,19 @@ fn grid(camera_query: Query<&Transform, With<Flycam>>, mut gizmos: Gizmos) {
         }
     };
 
-    let translation = camera.translation.trunc() * Vec3::new(1.0, 0.0, 1.0);
+    // Axis aligned orthographic views show the grid facing the camera
+    let (axis_a, axis_b) = match (flycam.view, projection) {
+        (Some(ViewDirection::Front | ViewDirection::Back), Projection::Orthographic(_)) => {
+            (Vec3::X, Vec3::Y)
+        }
+        (Some(ViewDirection::Right | ViewDirection::Left), Projection::Orthographic(_)) => {
+            (Vec3::Z, Vec3::Y)
+        }
+        _ => (Vec3::X, Vec3::Z),
+    };
+
+    let translation = camera.translation.trunc() * (axis_a + axis_b);
 
-    grid_axis(translation, 210.0, Vec3::X, Vec3::Z);
-    grid_axis(translation, 210.0, Vec3::Z, Vec3::X);
+    grid_axis(translation, 210.0, axis_a, axis_b);
+    grid_axis(translation, 210.0, axis_b, axis_a);
 }
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{observers::unique_name, EditorEntity, SelectedEntities};
+
+pub struct LibraryMaterial {
+    /// Strong for materials made in the editor, which stay loaded while no entity uses them,
+    /// and weak for the rest, which unload with their last user
+    pub handle: Handle<StandardMaterial>,
+    pub name: String,
+}
+
+/// Every [`StandardMaterial`] with an editor name, so materials can be found and shared by name
+#[derive(Resource, Default)]
+pub struct MaterialLibrary(pub HashMap<AssetId<StandardMaterial>, LibraryMaterial>);
+
+impl MaterialLibrary {
+    pub fn name(&self, id: AssetId<StandardMaterial>) -> &str {
+        self.0
+            .get(&id)
+            .map(|material| material.name.as_str())
+            .unwrap_or("Material")
+    }
+
+    /// `name`, or a variant of it that no other material has
+    pub fn unique_name(&self, name: &str) -> String {
+        let names = self
+            .0
+            .values()
+            .map(|material| material.name.clone())
+            .collect::<HashSet<String>>();
+
+        if names.contains(name) {
+            unique_name(name, &names)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// The materials sorted by name
+    pub fn sorted(&self) -> Vec<(AssetId<StandardMaterial>, &str)> {
+        let mut materials = self
+            .0
+            .iter()
+            .map(|(&id, material)| (id, material.name.as_str()))
+            .collect::<Vec<_>>();
+        materials.sort_by(|a, b| a.1.cmp(b.1));
+        materials
+    }
+
+    /// The names of the materials loaded from files, by asset path
+    pub fn loaded_names(&self, asset_server: &AssetServer) -> BTreeMap<String, String> {
+        self.0
+            .iter()
+            .filter_map(|(&id, material)| {
+                let path = asset_server.get_path(id)?;
+                Some((path.to_string(), material.name.clone()))
+            })
+            .collect()
+    }
+
+    fn insert(&mut self, handle: Handle<StandardMaterial>, name: &str) {
+        let name = self.unique_name(name);
+        self.0.insert(handle.id(), LibraryMaterial { handle, name });
+    }
+}
+
+/// Names of loaded materials by asset path, restored from the open scene's editor metadata
+#[derive(Resource, Default)]
+pub struct MaterialNames(pub BTreeMap<String, String>);
+
+/// Renames the loaded materials in the library after `names`,
+/// and keeps them to name the materials that are loaded later
+pub fn restore_names(world: &mut World, names: BTreeMap<String, String>) {
+    world.resource_scope(|world, mut library: Mut<MaterialLibrary>| {
+        let asset_server = world.resource::<AssetServer>();
+        for (&id, material) in library.0.iter_mut() {
+            if let Some(name) = asset_server
+                .get_path(id)
+                .and_then(|path| names.get(&path.to_string()))
+            {
+                material.name.clone_from(name);
+            }
+        }
+    });
+    world.insert_resource(MaterialNames(names));
+}
+
+/// Adds new materials to the library, including the ones loaded with scenes.
+/// Loaded materials keep their saved name, or are named after their label,
+/// like `Material0` in a glTF file.
+fn add_materials(
+    mut events: EventReader<AssetEvent<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    names: Res<MaterialNames>,
+    mut library: ResMut<MaterialLibrary>,
+) {
+    for event in events.read() {
+        match *event {
+            AssetEvent::Added { id } if !library.0.contains_key(&id) => {
+                let path = asset_server.get_path(id);
+                let name = path
+                    .as_ref()
+                    .and_then(|path| {
+                        names
+                            .0
+                            .get(&path.to_string())
+                            .map(String::as_str)
+                            .or_else(|| path.label())
+                    })
+                    .unwrap_or("Material");
+                library.insert(Handle::Weak(id), name);
+            }
+            AssetEvent::Removed { id } => {
+                library.0.remove(&id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The number of scene entities using each material
+pub fn material_users(world: &mut World) -> HashMap<AssetId<StandardMaterial>, usize> {
+    let mut users = HashMap::new();
+    for handle in world
+        .query_filtered::<&Handle<StandardMaterial>, Without<EditorEntity>>()
+        .iter(world)
+    {
+        *users.entry(handle.id()).or_default() += 1;
+    }
+    users
+}
+
+/// Adds a default material to the library
+pub fn new_material(world: &mut World) -> Handle<StandardMaterial> {
+    let handle = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    world
+        .resource_mut::<MaterialLibrary>()
+        .insert(handle.clone(), "Material");
+    handle
+}
+
+/// Adds a copy of a material to the library, named after the original
+pub fn duplicate_material(
+    world: &mut World,
+    id: AssetId<StandardMaterial>,
+) -> Option<Handle<StandardMaterial>> {
+    let material = world
+        .resource::<Assets<StandardMaterial>>()
+        .get(id)?
+        .clone();
+    let handle = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(material);
+
+    let mut library = world.resource_mut::<MaterialLibrary>();
+    let name = library.name(id).to_string();
+    library.insert(handle.clone(), &name);
+    Some(handle)
+}
+
+/// Assigns a material to the selected entities that have one
+pub fn assign_material(world: &mut World, id: AssetId<StandardMaterial>) {
+    let Some(handle) = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .get_strong_handle(id)
+    else {
+        return;
+    };
+
+    let selected = world.resource::<SelectedEntities>().0.clone();
+    for entity in selected {
+        if let Some(mut material) = world.get_mut::<Handle<StandardMaterial>>(entity) {
+            *material = handle.clone();
+        }
+    }
+}
+
+/// Removes an unused material from the library, which unloads it once nothing else holds it
+pub fn remove_material(world: &mut World, id: AssetId<StandardMaterial>) {
+    world.resource_mut::<MaterialLibrary>().0.remove(&id);
+}
+
+pub struct MaterialLibraryPlugin;
+impl Plugin for MaterialLibraryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MaterialLibrary::default());
+        app.init_resource::<MaterialNames>();
+        app.add_systems(PostUpdate, add_materials);
+    }
+}
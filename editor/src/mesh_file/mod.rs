@@ -0,0 +1,226 @@
+mod obj;
+mod ply;
+mod stl;
+
+use std::{io, path::Path};
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        primitives::Aabb,
+        render_asset::RenderAssetUsages,
+        view::VisibilitySystems,
+    },
+};
+
+use crate::{asset_path, SelectedEntities};
+
+/// A mesh from an OBJ, STL or PLY file, loaded again when the scene is opened.
+/// Only the path needs to be saved, the mesh is loaded when it changes
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct MeshFile {
+    /// Relative to the `assets` folder, or absolute for files outside it
+    pub path: String,
+    /// The index of the mesh in the file, OBJ files can have several
+    pub part: usize,
+}
+
+impl MeshFile {
+    /// The labeled [`Mesh`] asset of the part
+    fn mesh_path(&self) -> String {
+        format!("{}#Mesh{}", self.path, self.part)
+    }
+}
+
+pub struct MeshPart {
+    pub name: String,
+    pub mesh: Handle<Mesh>,
+    pub material: Option<Handle<StandardMaterial>>,
+}
+
+/// The meshes of a file, which are also its `Mesh{index}` labeled assets
+#[derive(Asset, TypePath)]
+pub struct MeshFileAsset {
+    pub parts: Vec<MeshPart>,
+}
+
+/// Mesh data read from a file, before it becomes a [`Mesh`]
+#[derive(Default)]
+struct ParsedMesh {
+    positions: Vec<[f32; 3]>,
+    normals: Option<Vec<[f32; 3]>>,
+    uvs: Option<Vec<[f32; 2]>>,
+    /// sRGB vertex colors
+    colors: Option<Vec<[f32; 4]>>,
+    /// Every three positions are a triangle without indices
+    indices: Option<Vec<u32>>,
+}
+
+impl ParsedMesh {
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+
+        if let Some(uvs) = self.uvs {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        }
+        if let Some(colors) = self.colors {
+            let colors = colors
+                .into_iter()
+                .map(|[r, g, b, a]| LinearRgba::from(Srgba::new(r, g, b, a)).to_f32_array())
+                .collect::<Vec<_>>();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+        if let Some(indices) = self.indices {
+            mesh.insert_indices(Indices::U32(indices));
+        }
+
+        // Smooth normals for indexed meshes, flat ones otherwise
+        match self.normals {
+            Some(normals) => mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals),
+            None => mesh.compute_normals(),
+        }
+
+        // Normal maps need tangents, which need UVs
+        if mesh.contains_attribute(Mesh::ATTRIBUTE_UV_0) {
+            if let Err(e) = mesh.generate_tangents() {
+                warn!("Failed to generate tangents: {e}");
+            }
+        }
+
+        mesh
+    }
+}
+
+fn invalid_data(message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Splits a polygon into a fan of triangles
+fn triangulate(polygon: &[u32], indices: &mut Vec<u32>) {
+    for i in 1..polygon.len().saturating_sub(1) {
+        indices.extend([polygon[0], polygon[i], polygon[i + 1]]);
+    }
+}
+
+/// A file being imported, its parts are spawned once it loads
+#[derive(Component)]
+struct ImportingMeshFile {
+    path: String,
+    handle: Handle<MeshFileAsset>,
+}
+
+/// Gives imported entities their parts, on the entity itself for files with one mesh
+/// and as children otherwise
+fn spawn_imported(
+    query: Query<(Entity, &ImportingMeshFile)>,
+    mesh_files: Res<Assets<MeshFileAsset>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (entity, importing) in query.iter() {
+        if let bevy::asset::LoadState::Failed(e) = asset_server.load_state(&importing.handle) {
+            error!("Failed to import mesh: {:?}\n{e:?}", importing.path);
+            commands.entity(entity).remove::<ImportingMeshFile>();
+            continue;
+        }
+
+        let Some(mesh_file) = mesh_files.get(&importing.handle) else {
+            continue;
+        };
+        commands.entity(entity).remove::<ImportingMeshFile>();
+
+        let mut material = |part: &MeshPart| {
+            part.material
+                .clone()
+                .unwrap_or_else(|| materials.add(StandardMaterial::default()))
+        };
+
+        if let [part] = mesh_file.parts.as_slice() {
+            commands.entity(entity).insert((
+                MeshFile {
+                    path: importing.path.clone(),
+                    part: 0,
+                },
+                part.mesh.clone(),
+                material(part),
+            ));
+            continue;
+        }
+
+        for (index, part) in mesh_file.parts.iter().enumerate() {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    MeshFile {
+                        path: importing.path.clone(),
+                        part: index,
+                    },
+                    part.mesh.clone(),
+                    material(part),
+                    Name::new(part.name.clone()),
+                ))
+                .set_parent(entity);
+        }
+    }
+}
+
+/// Loads the meshes of changed mesh files, including the ones loaded from scenes
+fn update_meshes(
+    query: Query<(Entity, &MeshFile), Changed<MeshFile>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (entity, mesh_file) in query.iter() {
+        // The `Aabb` is removed to be computed again for the new mesh
+        commands
+            .entity(entity)
+            .insert(asset_server.load::<Mesh>(mesh_file.mesh_path()))
+            .remove::<Aabb>();
+    }
+}
+
+/// Spawns and selects an entity that imports `path`, placed at `transform`
+pub fn import(world: &mut World, path: &Path, transform: Transform) -> Entity {
+    let path = asset_path(path);
+    let name = Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Mesh".to_string());
+
+    let handle = world.resource::<AssetServer>().load(path.clone());
+    let entity = world
+        .spawn((
+            SpatialBundle::from_transform(transform),
+            Name::new(name),
+            ImportingMeshFile { path, handle },
+        ))
+        .id();
+
+    let mut selected = world.resource_mut::<SelectedEntities>();
+    selected.0.clear();
+    selected.0.insert(entity);
+    entity
+}
+
+pub struct MeshFilePlugin;
+impl Plugin for MeshFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MeshFile>();
+        app.init_asset::<MeshFileAsset>();
+        app.register_asset_loader(obj::ObjLoader);
+        app.register_asset_loader(stl::StlLoader);
+        app.register_asset_loader(ply::PlyLoader);
+        app.add_systems(Update, spawn_imported);
+        app.add_systems(
+            PostUpdate,
+            update_meshes.before(VisibilitySystems::CalculateBounds),
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    render::texture::ImageLoaderSettings,
+    utils::HashMap,
+};
+
+use super::{invalid_data, triangulate, MeshFileAsset, MeshPart, ParsedMesh};
+
+/// Loads Wavefront OBJ files with their MTL materials,
+/// each object or group with its own material is a part
+pub struct ObjLoader;
+
+impl AssetLoader for ObjLoader {
+    type Asset = MeshFileAsset;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<MeshFileAsset, io::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let obj = parse_obj(&String::from_utf8_lossy(&bytes))?;
+
+        // Material libraries and textures are relative to the file
+        let directory = load_context
+            .path()
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut materials = HashMap::new();
+        for library in obj.material_libraries.iter() {
+            let bytes = match load_context.read_asset_bytes(directory.join(library)).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warn!("Failed to read material library {library:?}: {e}");
+                    continue;
+                }
+            };
+
+            for material in parse_mtl(&String::from_utf8_lossy(&bytes)) {
+                let name = material.name.clone();
+                let material = material.into_material(load_context, &directory);
+                let handle = load_context
+                    .add_labeled_asset(format!("Material{}", materials.len()), material);
+                materials.insert(name, handle);
+            }
+        }
+
+        let parts = obj
+            .parts
+            .into_iter()
+            .enumerate()
+            .map(|(index, part)| MeshPart {
+                name: part.name,
+                mesh: load_context.add_labeled_asset(format!("Mesh{index}"), part.mesh.into_mesh()),
+                material: part
+                    .material
+                    .and_then(|material| materials.get(&material).cloned()),
+            })
+            .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            return Err(invalid_data("The file has no faces"));
+        }
+
+        Ok(MeshFileAsset { parts })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["obj"]
+    }
+}
+
+struct ObjPart {
+    name: String,
+    material: Option<String>,
+    mesh: ParsedMesh,
+}
+
+struct ParsedObj {
+    parts: Vec<ObjPart>,
+    material_libraries: Vec<String>,
+}
+
+/// The faces of a part, by the `(position, uv, normal)` indices of their vertices in the file
+struct PartBuilder {
+    name: String,
+    material: Option<String>,
+    vertices: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+    keys: Vec<(usize, Option<usize>, Option<usize>)>,
+    indices: Vec<u32>,
+}
+
+impl PartBuilder {
+    fn vertex(&mut self, key: (usize, Option<usize>, Option<usize>)) -> u32 {
+        *self.vertices.entry(key).or_insert_with(|| {
+            self.keys.push(key);
+            self.keys.len() as u32 - 1
+        })
+    }
+
+    fn build(
+        self,
+        positions: &[[f32; 3]],
+        colors: Option<&[[f32; 4]]>,
+        uvs: &[[f32; 2]],
+        normals: &[[f32; 3]],
+    ) -> ObjPart {
+        // Attributes only some vertices have are dropped, normals are generated instead
+        let has_uvs = self.keys.iter().all(|(_, uv, _)| uv.is_some());
+        let has_normals = self.keys.iter().all(|(_, _, normal)| normal.is_some());
+
+        let mesh = ParsedMesh {
+            positions: self.keys.iter().map(|key| positions[key.0]).collect(),
+            normals: has_normals.then(|| {
+                self.keys
+                    .iter()
+                    .filter_map(|key| Some(normals[key.2?]))
+                    .collect()
+            }),
+            // OBJ UVs start at the bottom
+            uvs: has_uvs.then(|| {
+                self.keys
+                    .iter()
+                    .filter_map(|key| key.1)
+                    .map(|index| [uvs[index][0], 1.0 - uvs[index][1]])
+                    .collect()
+            }),
+            colors: colors.map(|colors| self.keys.iter().map(|key| colors[key.0]).collect()),
+            indices: Some(self.indices),
+        };
+
+        ObjPart {
+            name: self.name,
+            material: self.material,
+            mesh,
+        }
+    }
+}
+
+/// Resolves a 1 based index, negative indices count back from the last element
+fn resolve_index(token: &str, len: usize) -> io::Result<usize> {
+    let index = token
+        .parse::<i64>()
+        .map_err(|_| invalid_data(format!("Invalid index: {token}")))?;
+
+    let resolved = if index < 0 {
+        len as i64 + index
+    } else {
+        index - 1
+    };
+
+    if (0..len as i64).contains(&resolved) {
+        Ok(resolved as usize)
+    } else {
+        Err(invalid_data(format!("Index out of range: {token}")))
+    }
+}
+
+fn floats<const N: usize>(arguments: &str) -> io::Result<[f32; N]> {
+    let mut values = [0.0; N];
+    let mut tokens = arguments.split_whitespace();
+    for value in values.iter_mut() {
+        let token = tokens.next().unwrap_or("0");
+        *value = token
+            .parse()
+            .map_err(|_| invalid_data(format!("Invalid number: {token}")))?;
+    }
+    Ok(values)
+}
+
+fn parse_obj(text: &str) -> io::Result<ParsedObj> {
+    let mut positions = Vec::new();
+    // Only set by files with `v x y z r g b` vertices
+    let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+    let mut material_libraries = Vec::new();
+
+    let mut name = "Mesh".to_string();
+    let mut material = None;
+    let mut builders: Vec<PartBuilder> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        let (keyword, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let arguments = arguments.trim();
+
+        match keyword {
+            "v" => {
+                let values = arguments
+                    .split_whitespace()
+                    .map(|token| token.parse::<f32>())
+                    .collect::<Result<Vec<f32>, _>>()
+                    .map_err(|_| invalid_data(format!("Invalid vertex: {line}")))?;
+                if values.len() < 3 {
+                    return Err(invalid_data(format!("Invalid vertex: {line}")));
+                }
+
+                positions.push([values[0], values[1], values[2]]);
+                if values.len() >= 6 {
+                    colors.resize(positions.len() - 1, [1.0; 4]);
+                    colors.push([values[3], values[4], values[5], 1.0]);
+                }
+            }
+            "vt" => uvs.push(floats::<2>(arguments)?),
+            "vn" => normals.push(floats::<3>(arguments)?),
+            "o" | "g" if !arguments.is_empty() => name = arguments.to_string(),
+            "usemtl" => material = Some(arguments.to_string()),
+            "mtllib" => material_libraries.push(arguments.replace('\\', "/")),
+            "f" => {
+                let needs_part = builders
+                    .last()
+                    .is_none_or(|part| part.name != name || part.material != material);
+                if needs_part {
+                    builders.push(PartBuilder {
+                        name: name.clone(),
+                        material: material.clone(),
+                        vertices: HashMap::new(),
+                        keys: Vec::new(),
+                        indices: Vec::new(),
+                    });
+                }
+                let part = builders.last_mut().unwrap();
+
+                // `v`, `v/vt`, `v//vn` or `v/vt/vn`
+                let mut polygon = Vec::new();
+                for vertex in arguments.split_whitespace() {
+                    let mut indices = vertex.split('/');
+                    let position = resolve_index(indices.next().unwrap_or(""), positions.len())?;
+                    let uv = match indices.next() {
+                        Some(token) if !token.is_empty() => Some(resolve_index(token, uvs.len())?),
+                        _ => None,
+                    };
+                    let normal = match indices.next() {
+                        Some(token) if !token.is_empty() => {
+                            Some(resolve_index(token, normals.len())?)
+                        }
+                        _ => None,
+                    };
+
+                    polygon.push(part.vertex((position, uv, normal)));
+                }
+
+                triangulate(&polygon, &mut part.indices);
+            }
+            _ => {}
+        }
+    }
+
+    if !colors.is_empty() {
+        colors.resize(positions.len(), [1.0; 4]);
+    }
+    let colors = (!colors.is_empty()).then_some(colors.as_slice());
+
+    let parts = builders
+        .into_iter()
+        .filter(|part| !part.indices.is_empty())
+        .map(|part| part.build(&positions, colors, &uvs, &normals))
+        .collect();
+
+    Ok(ParsedObj {
+        parts,
+        material_libraries,
+    })
+}
+
+#[derive(Default)]
+struct ObjMaterial {
+    name: String,
+    diffuse: Option<[f32; 3]>,
+    alpha: Option<f32>,
+    emissive: Option<[f32; 3]>,
+    shininess: Option<f32>,
+    roughness: Option<f32>,
+    metallic: Option<f32>,
+    diffuse_texture: Option<String>,
+    normal_texture: Option<String>,
+    emissive_texture: Option<String>,
+}
+
+impl ObjMaterial {
+    fn into_material(self, load_context: &mut LoadContext, directory: &Path) -> StandardMaterial {
+        let path = |file: String| -> PathBuf { directory.join(file) };
+
+        let [r, g, b] = self.diffuse.unwrap_or([1.0; 3]);
+        let alpha = self.alpha.unwrap_or(1.0);
+
+        // Phong shininess to roughness, as most OBJ exporters only write `Ns`
+        let perceptual_roughness = self.roughness.unwrap_or_else(|| {
+            self.shininess
+                .map(|shininess| (2.0 / (shininess.max(0.0) + 2.0)).sqrt().sqrt())
+                .unwrap_or(0.5)
+        });
+
+        let emissive = match (self.emissive, &self.emissive_texture) {
+            (Some([r, g, b]), _) => LinearRgba::rgb(r, g, b),
+            // The texture is multiplied by the emissive color
+            (None, Some(_)) => LinearRgba::WHITE,
+            (None, None) => LinearRgba::BLACK,
+        };
+
+        StandardMaterial {
+            base_color: Color::srgba(r, g, b, alpha),
+            base_color_texture: self
+                .diffuse_texture
+                .map(|file| load_context.load(path(file))),
+            normal_map_texture: self.normal_texture.map(|file| {
+                load_context
+                    .loader()
+                    .with_settings(|settings: &mut ImageLoaderSettings| settings.is_srgb = false)
+                    .load(path(file))
+            }),
+            emissive,
+            emissive_texture: self
+                .emissive_texture
+                .map(|file| load_context.load(path(file))),
+            perceptual_roughness,
+            metallic: self.metallic.unwrap_or(0.0),
+            alpha_mode: if alpha < 1.0 {
+                AlphaMode::Blend
+            } else {
+                AlphaMode::Opaque
+            },
+            ..default()
+        }
+    }
+}
+
+/// The file of a texture statement, after options like `-bm 1.0` or `-clamp on`
+fn texture_file(arguments: &str) -> Option<String> {
+    let mut tokens = arguments.split_whitespace().peekable();
+    while tokens.next_if(|token| token.starts_with('-')).is_some() {
+        while tokens
+            .next_if(|value| value.parse::<f32>().is_ok() || matches!(*value, "on" | "off"))
+            .is_some()
+        {}
+    }
+
+    let file = tokens.collect::<Vec<_>>().join(" ").replace('\\', "/");
+    (!file.is_empty()).then_some(file)
+}
+
+fn parse_mtl(text: &str) -> Vec<ObjMaterial> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        let (keyword, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let arguments = arguments.trim();
+
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial {
+                name: arguments.to_string(),
+                ..default()
+            });
+            continue;
+        }
+
+        let Some(material) = materials.last_mut() else {
+            continue;
+        };
+        let float = arguments
+            .split_whitespace()
+            .next()
+            .and_then(|token| token.parse::<f32>().ok());
+
+        match keyword {
+            "Kd" => material.diffuse = floats::<3>(arguments).ok(),
+            "Ke" => material.emissive = floats::<3>(arguments).ok(),
+            "d" => material.alpha = float,
+            "Tr" => material.alpha = float.map(|transparency| 1.0 - transparency),
+            "Ns" => material.shininess = float,
+            "Pr" => material.roughness = float,
+            "Pm" => material.metallic = float,
+            "map_Kd" => material.diffuse_texture = texture_file(arguments),
+            "map_Ke" => material.emissive_texture = texture_file(arguments),
+            "norm" | "map_Bump" | "map_bump" | "bump" => {
+                material.normal_texture = texture_file(arguments)
+            }
+            _ => {}
+        }
+    }
+
+    materials
+}
@@ -0,0 +1,389 @@
+use std::io;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+
+use super::{invalid_data, triangulate, MeshFileAsset, MeshPart, ParsedMesh};
+
+/// Loads ASCII and binary PLY files as a single part,
+/// with the normals, colors and UVs of their vertices
+pub struct PlyLoader;
+
+impl AssetLoader for PlyLoader {
+    type Asset = MeshFileAsset;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<MeshFileAsset, io::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mesh = parse_ply(&bytes)?;
+        if mesh.indices.as_ref().is_none_or(Vec::is_empty) {
+            return Err(invalid_data("The file has no faces"));
+        }
+
+        Ok(MeshFileAsset {
+            parts: vec![MeshPart {
+                name: "Mesh".to_string(),
+                mesh: load_context.add_labeled_asset("Mesh0".to_string(), mesh.into_mesh()),
+                material: None,
+            }],
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ply"]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> io::Result<Self> {
+        Ok(match name {
+            "char" | "int8" => Self::I8,
+            "uchar" | "uint8" => Self::U8,
+            "short" | "int16" => Self::I16,
+            "ushort" | "uint16" => Self::U16,
+            "int" | "int32" => Self::I32,
+            "uint" | "uint32" => Self::U32,
+            "float" | "float32" => Self::F32,
+            "double" | "float64" => Self::F64,
+            _ => return Err(invalid_data(format!("Unknown property type: {name}"))),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+}
+
+enum PropertyType {
+    Scalar(ScalarType),
+    /// The type of the length, then the type of the items
+    List(ScalarType, ScalarType),
+}
+
+struct Property {
+    name: String,
+    kind: PropertyType,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    fn property(&self, names: &[&str]) -> Option<usize> {
+        self.properties
+            .iter()
+            .position(|property| names.contains(&property.name.as_str()))
+    }
+}
+
+struct Header {
+    format: Format,
+    elements: Vec<Element>,
+    /// Where the element data starts
+    len: usize,
+}
+
+fn parse_header(bytes: &[u8]) -> io::Result<Header> {
+    if !bytes.starts_with(b"ply") {
+        return Err(invalid_data("The file doesn't start with `ply`"));
+    }
+
+    let end = bytes
+        .windows(b"end_header".len())
+        .position(|window| window == b"end_header")
+        .ok_or_else(|| invalid_data("The file has no `end_header`"))?;
+    let len = bytes[end..]
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .map(|newline| end + newline + 1)
+        .unwrap_or(bytes.len());
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in String::from_utf8_lossy(&bytes[..end]).lines() {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        match tokens.as_slice() {
+            ["format", name, _version] => {
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(invalid_data(format!("Unknown format: {name}"))),
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| invalid_data(format!("Invalid element count: {line}")))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", length, item, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid_data("A property isn't in an element"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    kind: PropertyType::List(ScalarType::parse(length)?, ScalarType::parse(item)?),
+                });
+            }
+            ["property", kind, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| invalid_data("A property isn't in an element"))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    kind: PropertyType::Scalar(ScalarType::parse(kind)?),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Header {
+        format: format.ok_or_else(|| invalid_data("The file has no format"))?,
+        elements,
+        len,
+    })
+}
+
+/// The values of the elements, in the order of their properties
+enum Values<'a> {
+    Ascii(std::str::SplitWhitespace<'a>),
+    Binary { bytes: &'a [u8], big_endian: bool },
+}
+
+impl Values<'_> {
+    fn next(&mut self, kind: ScalarType) -> io::Result<f64> {
+        match self {
+            Self::Ascii(tokens) => tokens
+                .next()
+                .and_then(|token| token.parse::<f64>().ok())
+                .ok_or_else(|| invalid_data("A value is missing or invalid")),
+            Self::Binary { bytes, big_endian } => {
+                let size = kind.size();
+                let remaining: &[u8] = *bytes;
+                if remaining.len() < size {
+                    return Err(invalid_data("The file is too short"));
+                }
+                let (value, rest) = remaining.split_at(size);
+                *bytes = rest;
+
+                // Little endian from here on
+                let mut buffer = [0; 8];
+                buffer[..size].copy_from_slice(value);
+                if *big_endian {
+                    buffer[..size].reverse();
+                }
+
+                Ok(match kind {
+                    ScalarType::I8 => buffer[0] as i8 as f64,
+                    ScalarType::U8 => buffer[0] as f64,
+                    ScalarType::I16 => i16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([buffer[0], buffer[1]]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes(buffer[..4].try_into().unwrap()) as f64,
+                    ScalarType::U32 => u32::from_le_bytes(buffer[..4].try_into().unwrap()) as f64,
+                    ScalarType::F32 => f32::from_le_bytes(buffer[..4].try_into().unwrap()) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(buffer),
+                })
+            }
+        }
+    }
+
+    /// Reads a row of an element, the items of lists are appended to `lists`
+    fn row(
+        &mut self,
+        element: &Element,
+        scalars: &mut Vec<f64>,
+        lists: &mut Vec<Vec<f64>>,
+    ) -> io::Result<()> {
+        scalars.clear();
+        lists.clear();
+
+        for property in element.properties.iter() {
+            match property.kind {
+                PropertyType::Scalar(kind) => scalars.push(self.next(kind)?),
+                PropertyType::List(length, item) => {
+                    // Keeps scalar indices in the order of the properties
+                    scalars.push(0.0);
+
+                    // The length isn't trusted to reserve memory, the list stops growing
+                    // when the file runs out of values
+                    let length = self.next(length)? as usize;
+                    let mut list = Vec::new();
+                    for _ in 0..length {
+                        list.push(self.next(item)?);
+                    }
+                    lists.push(list);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Integer colors are scaled to 0..1, float colors already are
+fn color(value: f64, kind: &PropertyType) -> f32 {
+    match kind {
+        PropertyType::Scalar(ScalarType::U8) => value as f32 / 255.0,
+        PropertyType::Scalar(ScalarType::U16) => value as f32 / 65535.0,
+        _ => value as f32,
+    }
+}
+
+fn parse_ply(bytes: &[u8]) -> io::Result<ParsedMesh> {
+    let header = parse_header(bytes)?;
+    let data = &bytes[header.len..];
+
+    let mut values = match header.format {
+        Format::Ascii => Values::Ascii(
+            std::str::from_utf8(data)
+                .unwrap_or_default()
+                .split_whitespace(),
+        ),
+        Format::BinaryLittleEndian | Format::BinaryBigEndian => Values::Binary {
+            bytes: data,
+            big_endian: header.format == Format::BinaryBigEndian,
+        },
+    };
+
+    let mut mesh = ParsedMesh::default();
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+
+    let mut scalars = Vec::new();
+    let mut lists = Vec::new();
+    for element in header.elements.iter() {
+        match element.name.as_str() {
+            "vertex" => {
+                let position = [
+                    element.property(&["x"]),
+                    element.property(&["y"]),
+                    element.property(&["z"]),
+                ];
+                let [Some(x), Some(y), Some(z)] = position else {
+                    return Err(invalid_data("The vertices have no position"));
+                };
+                let normal = [
+                    element.property(&["nx"]),
+                    element.property(&["ny"]),
+                    element.property(&["nz"]),
+                ];
+                let rgb = [
+                    element.property(&["red", "r"]),
+                    element.property(&["green", "g"]),
+                    element.property(&["blue", "b"]),
+                ];
+                let alpha = element.property(&["alpha", "a"]);
+                let uv = [
+                    element.property(&["s", "u", "texture_u"]),
+                    element.property(&["t", "v", "texture_v"]),
+                ];
+
+                for _ in 0..element.count {
+                    values.row(element, &mut scalars, &mut lists)?;
+                    mesh.positions
+                        .push([scalars[x] as f32, scalars[y] as f32, scalars[z] as f32]);
+
+                    if let [Some(nx), Some(ny), Some(nz)] = normal {
+                        normals.push([scalars[nx] as f32, scalars[ny] as f32, scalars[nz] as f32]);
+                    }
+                    if let [Some(r), Some(g), Some(b)] = rgb {
+                        let kind = &element.properties[r].kind;
+                        colors.push([
+                            color(scalars[r], kind),
+                            color(scalars[g], kind),
+                            color(scalars[b], kind),
+                            alpha.map_or(1.0, |a| color(scalars[a], &element.properties[a].kind)),
+                        ]);
+                    }
+                    // PLY UVs start at the bottom
+                    if let [Some(u), Some(v)] = uv {
+                        uvs.push([scalars[u] as f32, 1.0 - scalars[v] as f32]);
+                    }
+                }
+            }
+            "face" => {
+                let Some(property) = element
+                    .property(&["vertex_indices", "vertex_index"])
+                    .filter(|&property| {
+                        matches!(element.properties[property].kind, PropertyType::List(..))
+                    })
+                else {
+                    return Err(invalid_data("The faces have no vertex indices"));
+                };
+                // The position of the list among the lists of a row
+                let list = element.properties[..property]
+                    .iter()
+                    .filter(|property| matches!(property.kind, PropertyType::List(..)))
+                    .count();
+
+                for _ in 0..element.count {
+                    values.row(element, &mut scalars, &mut lists)?;
+
+                    let polygon = lists[list]
+                        .iter()
+                        .map(|&index| index as u32)
+                        .collect::<Vec<u32>>();
+                    if let Some(index) = polygon
+                        .iter()
+                        .find(|&&index| index as usize >= mesh.positions.len())
+                    {
+                        return Err(invalid_data(format!("Vertex index out of range: {index}")));
+                    }
+
+                    triangulate(&polygon, &mut indices);
+                }
+            }
+            // Other elements like edges are read to get to the ones after them
+            _ => {
+                for _ in 0..element.count {
+                    values.row(element, &mut scalars, &mut lists)?;
+                }
+            }
+        }
+    }
+
+    mesh.normals = (!normals.is_empty()).then_some(normals);
+    mesh.colors = (!colors.is_empty()).then_some(colors);
+    mesh.uvs = (!uvs.is_empty()).then_some(uvs);
+    mesh.indices = Some(indices);
+    Ok(mesh)
+}
@@ -0,0 +1,114 @@
+use std::io;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+
+use super::{invalid_data, MeshFileAsset, MeshPart, ParsedMesh};
+
+/// Loads binary and ASCII STL files as a single part
+pub struct StlLoader;
+
+impl AssetLoader for StlLoader {
+    type Asset = MeshFileAsset;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<MeshFileAsset, io::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let positions = parse_stl(&bytes)?;
+        if positions.is_empty() {
+            return Err(invalid_data("The file has no triangles"));
+        }
+
+        // Facet normals are often zero or inconsistent, they're generated instead
+        let mesh = ParsedMesh {
+            positions,
+            ..Default::default()
+        };
+
+        Ok(MeshFileAsset {
+            parts: vec![MeshPart {
+                name: "Mesh".to_string(),
+                mesh: load_context.add_labeled_asset("Mesh0".to_string(), mesh.into_mesh()),
+                material: None,
+            }],
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["stl"]
+    }
+}
+
+/// The positions of the triangles, three per triangle
+fn parse_stl(bytes: &[u8]) -> io::Result<Vec<[f32; 3]>> {
+    // Binary files can also start with `solid`, so the size is checked first
+    let binary_len = bytes
+        .get(80..84)
+        .map(|count| 84 + 50 * u32::from_le_bytes(count.try_into().unwrap()) as usize);
+    if binary_len == Some(bytes.len()) || !bytes.starts_with(b"solid") {
+        return parse_binary(bytes);
+    }
+
+    parse_ascii(&String::from_utf8_lossy(bytes))
+}
+
+/// An 80 byte header, the triangle count,
+/// then a normal, three vertices and two attribute bytes per triangle
+fn parse_binary(bytes: &[u8]) -> io::Result<Vec<[f32; 3]>> {
+    let count = bytes
+        .get(80..84)
+        .map(|count| u32::from_le_bytes(count.try_into().unwrap()) as usize)
+        .ok_or_else(|| invalid_data("The file is too short"))?;
+    let triangles = bytes
+        .get(84..84 + count * 50)
+        .ok_or_else(|| invalid_data("The file has fewer triangles than its header"))?;
+
+    let float = |bytes: &[u8]| f32::from_le_bytes(bytes.try_into().unwrap());
+
+    let mut positions = Vec::with_capacity(count * 3);
+    for triangle in triangles.chunks_exact(50) {
+        for vertex in triangle[12..48].chunks_exact(12) {
+            positions.push([
+                float(&vertex[0..4]),
+                float(&vertex[4..8]),
+                float(&vertex[8..12]),
+            ]);
+        }
+    }
+
+    Ok(positions)
+}
+
+/// `facet normal`, `outer loop`, three `vertex x y z`, `endloop`, `endfacet` per triangle
+fn parse_ascii(text: &str) -> io::Result<Vec<[f32; 3]>> {
+    let mut positions = Vec::new();
+    for line in text.lines() {
+        let Some(coordinates) = line.trim().strip_prefix("vertex") else {
+            continue;
+        };
+
+        let values = coordinates
+            .split_whitespace()
+            .map(|token| token.parse::<f32>())
+            .collect::<Result<Vec<f32>, _>>()
+            .map_err(|_| invalid_data(format!("Invalid vertex: {line}")))?;
+        let [x, y, z] = values[..] else {
+            return Err(invalid_data(format!("Invalid vertex: {line}")));
+        };
+
+        positions.push([x, y, z]);
+    }
+
+    if positions.len() % 3 != 0 {
+        return Err(invalid_data("A facet doesn't have three vertices"));
+    }
+
+    Ok(positions)
+}
@@ -1,10 +1,16 @@
-use bevy::prelude::*;
+use std::any::TypeId;
 
-use crate::{EditorAction, EditorEntity, SelectedEntities};
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    transform::observers::TransformSelected, EditorAction, EditorEntity, SelectedEntities,
+};
 
 pub fn setup(mut commands: Commands) {
     commands.spawn((Observer::new(select_entity), EditorEntity));
     commands.spawn((Observer::new(delete_selected), EditorEntity));
+    commands.spawn((Observer::new(duplicate_selected), EditorEntity));
+    commands.spawn((Observer::new(reparent), EditorEntity));
 }
 
 #[derive(Event)]
@@ -46,6 +52,196 @@ fn delete_selected(
     }
 }
 
+/// Clones the selected entities and their descendants, selects the clones
+/// and starts moving them
+#[derive(Event)]
+pub struct DuplicateSelected;
+
+fn duplicate_selected(
+    _trigger: Trigger<DuplicateSelected>,
+    selected: Res<SelectedEntities>,
+    parent_query: Query<&Parent>,
+    mut commands: Commands,
+) {
+    // Selected descendants are already cloned with their selected ancestor
+    let entities = selected
+        .0
+        .iter()
+        .filter(|&&entity| {
+            !parent_query
+                .iter_ancestors(entity)
+                .any(|ancestor| selected.0.contains(&ancestor))
+        })
+        .cloned()
+        .collect::<Vec<Entity>>();
+
+    if entities.is_empty() {
+        info!("No entity is selected.");
+        return;
+    }
+
+    commands.add(move |world: &mut World| {
+        let mut names = entity_names(world);
+
+        let mut clones = Vec::new();
+        for entity in entities {
+            let clone = clone_entity(world, entity);
+
+            if let Some(parent) = world.get::<Parent>(entity).map(|parent| parent.get()) {
+                world.entity_mut(parent).add_child(clone);
+            }
+
+            rename_unique(world, clone, &mut names);
+            clones.push(clone);
+        }
+
+        world.resource_mut::<SelectedEntities>().0 = clones.into_iter().collect();
+        world.trigger(TransformSelected::Translate);
+    });
+}
+
+/// The names of every entity, used to give clones unique names
+pub fn entity_names(world: &mut World) -> HashSet<String> {
+    world
+        .query::<&Name>()
+        .iter(world)
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Gives `root` and its descendants names that aren't in `names`
+pub fn rename_unique(world: &mut World, root: Entity, names: &mut HashSet<String>) {
+    let mut stack = vec![root];
+    while let Some(entity) = stack.pop() {
+        if let Some(mut name) = world.get_mut::<Name>(entity) {
+            let new_name = unique_name(name.as_str(), names);
+            names.insert(new_name.clone());
+            name.set(new_name);
+        }
+
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter());
+        }
+    }
+}
+
+/// `Rock` -> `Rock.001`, `Rock.001` -> `Rock.002`, skipping names that are taken
+pub fn unique_name(name: &str, names: &HashSet<String>) -> String {
+    let base = match name.rsplit_once('.') {
+        Some((base, suffix))
+            if !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => name,
+    };
+
+    (1..)
+        .map(|n| format!("{base}.{n:03}"))
+        .find(|name| !names.contains(name))
+        .unwrap()
+}
+
+/// Spawns a copy of `entity` and its descendants using the reflected components
+pub fn clone_entity(world: &mut World, entity: Entity) -> Entity {
+    let clone = world.spawn_empty().id();
+
+    {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        let components = world
+            .inspect_entity(entity)
+            .into_iter()
+            .filter_map(|info| {
+                let type_id = info.type_id()?;
+                // The hierarchy is rebuilt below
+                if type_id == TypeId::of::<Parent>() || type_id == TypeId::of::<Children>() {
+                    return None;
+                }
+
+                let reflect_component = registry.get_type_data::<ReflectComponent>(type_id)?;
+                let value = reflect_component.reflect(world.entity(entity))?;
+                Some((reflect_component.clone(), value.clone_value()))
+            })
+            .collect::<Vec<_>>();
+
+        let mut clone_mut = world.entity_mut(clone);
+        for (reflect_component, value) in components {
+            reflect_component.insert(&mut clone_mut, &*value, &registry);
+        }
+    }
+
+    let children = world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    for child in children {
+        let child_clone = clone_entity(world, child);
+        world.entity_mut(clone).add_child(child_clone);
+    }
+
+    clone
+}
+
+/// Moves an entity to a new parent, or to the root if `parent` is `None`,
+/// keeping its world transform
+#[derive(Event, Clone, Copy)]
+pub struct Reparent {
+    pub entity: Entity,
+    pub parent: Option<Entity>,
+    /// The position in the parent's children, the entity is added last if `None`
+    pub index: Option<usize>,
+}
+
+fn reparent(
+    trigger: Trigger<Reparent>,
+    global_query: Query<&GlobalTransform>,
+    parent_query: Query<&Parent>,
+    children_query: Query<&Children>,
+    mut commands: Commands,
+) {
+    let Reparent {
+        entity,
+        parent,
+        index,
+    } = *trigger.event();
+
+    if let Some(parent) = parent {
+        if parent == entity || parent_query.iter_ancestors(parent).any(|a| a == entity) {
+            info!("An entity can't be parented to itself or its descendants.");
+            return;
+        }
+    }
+
+    let global_transform = global_query.get(entity).copied().unwrap_or_default();
+    let parent_transform = parent
+        .and_then(|parent| global_query.get(parent).ok())
+        .copied()
+        .unwrap_or_default();
+
+    match parent {
+        Some(parent) => {
+            let sibling_count = children_query
+                .get(parent)
+                .map(|children| children.iter().filter(|&&c| c != entity).count())
+                .unwrap_or(0);
+            let index = index.unwrap_or(sibling_count).min(sibling_count);
+
+            commands.entity(parent).insert_children(index, &[entity]);
+        }
+        None => {
+            commands.entity(entity).remove_parent();
+        }
+    }
+
+    if global_query.contains(entity) {
+        commands
+            .entity(entity)
+            .insert(global_transform.reparented_to(&parent_transform));
+    }
+}
+
 pub struct ObserverPlugin;
 impl Plugin for ObserverPlugin {
     fn build(&self, app: &mut App) {
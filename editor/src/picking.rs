@@ -1,11 +1,42 @@
-use bevy::prelude::*;
+use bevy::{color::palettes::css::ORANGE_RED, prelude::*};
 use bevy_mod_picking::prelude::*;
 
-use crate::observers::SelectEntity;
+use crate::{observers::SelectEntity, Locked};
 
-pub fn pointer_select(mut event_reader: EventReader<Pointer<Click>>, mut commands: Commands) {
+/// Where new entities are placed, set by Alt + clicking a surface.
+/// Without a position they're placed in front of the active camera
+#[derive(Resource, Default)]
+pub struct Cursor3d {
+    pub position: Option<Vec3>,
+}
+
+pub fn pointer_select(
+    mut event_reader: EventReader<Pointer<Click>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut cursor: ResMut<Cursor3d>,
+    locked_query: Query<(), With<Locked>>,
+    parent_query: Query<&Parent>,
+    mut commands: Commands,
+) {
     for event in event_reader.read() {
-        if event.button == PointerButton::Primary {
+        if event.button != PointerButton::Primary {
+            continue;
+        }
+
+        // Locked entities can still have the cursor placed on them
+        if keys.pressed(KeyCode::AltLeft) {
+            if let Some(position) = event.hit.position {
+                cursor.position = Some(position);
+            }
+            continue;
+        }
+
+        // Locked entities and their descendants can only be selected from the outliner
+        let is_locked = std::iter::once(event.target)
+            .chain(parent_query.iter_ancestors(event.target))
+            .any(|entity| locked_query.contains(entity));
+
+        if !is_locked {
             commands.trigger(SelectEntity {
                 target: event.target,
             });
@@ -13,10 +44,23 @@ pub fn pointer_select(mut event_reader: EventReader<Pointer<Click>>, mut command
     }
 }
 
+fn draw_cursor(cursor: Res<Cursor3d>, mut gizmos: Gizmos) {
+    let Some(position) = cursor.position else {
+        return;
+    };
+
+    gizmos.sphere(position, Quat::IDENTITY, 0.1, ORANGE_RED);
+    for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+        gizmos.line(position - axis * 0.25, position + axis * 0.25, ORANGE_RED);
+    }
+}
+
 pub struct PickingPlugin;
 impl Plugin for PickingPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(DefaultPickingPlugins);
+        app.insert_resource(Cursor3d::default());
         app.add_systems(PreUpdate, pointer_select);
+        app.add_systems(PostUpdate, draw_cursor);
     }
 }
@@ -0,0 +1,604 @@
+use std::{
+    any::TypeId,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use bevy::{
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    reflect::ReflectMut,
+    scene::DynamicEntity,
+    time::common_conditions::on_timer,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{asset_file, asset_path, command::scene, EditorEntity};
+
+/// The root of a placed prefab
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct PrefabInstance {
+    /// The `.prefab.ron` file, relative to the `assets` folder when it's inside it
+    pub path: String,
+    /// Components that keep their value when the prefab changes
+    pub overrides: Vec<PrefabOverride>,
+}
+
+impl PrefabInstance {
+    /// The prefab file, the same for every path that points to it,
+    /// including absolute paths saved by older scenes
+    fn file(&self) -> PathBuf {
+        asset_file(&asset_path(Path::new(&self.path)))
+    }
+}
+
+#[derive(Reflect, Default, Clone, PartialEq)]
+pub struct PrefabOverride {
+    /// The [`PrefabPart`] of the overridden entity
+    pub part: u64,
+    /// The type path of the overridden component
+    pub component: String,
+}
+
+/// Links an entity of an instance to its entity in the prefab file
+#[derive(Component, Reflect, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct PrefabPart(pub u64);
+
+struct LoadedPrefab {
+    scene: DynamicScene,
+    modified: Option<SystemTime>,
+}
+
+/// Prefab files used by instances, compared against the files on disk to propagate changes
+#[derive(Resource, Default)]
+pub struct Prefabs {
+    loaded: HashMap<PathBuf, LoadedPrefab>,
+    /// Files that failed to load, so they aren't retried every time
+    failed: HashSet<PathBuf>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn read_prefab(world: &World, path: &Path) -> Option<LoadedPrefab> {
+    let modified = modified(path);
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("Failed to read prefab: {path:?}\n{e:?}");
+            return None;
+        }
+    };
+
+    match scene::deserialize(world, &contents) {
+        Ok(scene) => Some(LoadedPrefab { scene, modified }),
+        Err(e) => {
+            error!("Failed to deserialize prefab: {path:?}\n{e:?}");
+            None
+        }
+    }
+}
+
+fn write_prefab(world: &World, path: &Path, scene: &DynamicScene) -> bool {
+    let serialized_scene = match scene::serialize(world, scene) {
+        Ok(serialized_scene) => serialized_scene,
+        Err(e) => {
+            error!("Failed to serialize prefab.\n{e:?}");
+            return false;
+        }
+    };
+
+    if let Err(e) = std::fs::write(path, serialized_scene) {
+        error!("Failed to write prefab to file: {path:?}\n{e:?}");
+        return false;
+    }
+
+    true
+}
+
+/// Loads the prefab at `path` if it isn't loaded yet, returns `false` if it can't be loaded
+fn load(world: &mut World, path: &Path) -> bool {
+    let prefabs = world.resource::<Prefabs>();
+    if prefabs.loaded.contains_key(path) {
+        return true;
+    }
+    if prefabs.failed.contains(path) {
+        return false;
+    }
+
+    let Some(prefab) = read_prefab(world, path) else {
+        world
+            .resource_mut::<Prefabs>()
+            .failed
+            .insert(path.to_path_buf());
+        return false;
+    };
+
+    world
+        .resource_mut::<Prefabs>()
+        .loaded
+        .insert(path.to_path_buf(), prefab);
+    true
+}
+
+fn is_type<T: 'static>(component: &dyn Reflect) -> bool {
+    component
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_id() == TypeId::of::<T>())
+}
+
+fn descendants(world: &World, root: Entity) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut stack = vec![root];
+    while let Some(entity) = stack.pop() {
+        if world.get::<EditorEntity>(entity).is_some() {
+            continue;
+        }
+
+        entities.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter());
+        }
+    }
+
+    entities
+}
+
+/// Rewrites entity ids and the [`Parent`] and [`Children`] links that point to them
+fn remap_entities(scene: &mut DynamicScene, ids: &EntityHashMap<Entity>) {
+    let map = |entity: &mut Entity| {
+        if let Some(id) = ids.get(entity) {
+            *entity = *id;
+        }
+    };
+
+    for dynamic_entity in scene.entities.iter_mut() {
+        map(&mut dynamic_entity.entity);
+
+        for component in dynamic_entity.components.iter_mut() {
+            if !is_type::<Parent>(&**component) && !is_type::<Children>(&**component) {
+                continue;
+            }
+
+            let ReflectMut::TupleStruct(tuple_struct) = component.reflect_mut() else {
+                continue;
+            };
+            let Some(field) = tuple_struct.field_mut(0) else {
+                continue;
+            };
+
+            if let Some(entity) = field.downcast_mut::<Entity>() {
+                map(entity);
+            } else if let ReflectMut::List(list) = field.reflect_mut() {
+                for index in 0..list.len() {
+                    if let Some(entity) = list
+                        .get_mut(index)
+                        .and_then(|entity| entity.downcast_mut::<Entity>())
+                    {
+                        map(entity);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Extracts `root` and its descendants, with the root at the origin.
+/// `ids` maps entities to their id in the prefab file, other entities keep their own
+fn build_prefab(world: &World, root: Entity, ids: &EntityHashMap<Entity>) -> DynamicScene {
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .allow_all()
+        .deny::<PrefabInstance>()
+        .deny::<PrefabPart>()
+        .extract_entities(descendants(world, root).into_iter())
+        .build();
+
+    // Instances are placed by their root transform
+    if let Some(dynamic_root) = scene.entities.iter_mut().find(|e| e.entity == root) {
+        dynamic_root
+            .components
+            .retain(|component| !is_type::<Parent>(&**component));
+
+        for component in dynamic_root.components.iter_mut() {
+            if is_type::<Transform>(&**component) {
+                *component = Box::new(Transform::IDENTITY);
+            }
+        }
+    }
+
+    remap_entities(&mut scene, ids);
+    scene
+}
+
+/// Saves `root` and its descendants as a prefab, turning them into its first instance.
+/// Nested instances are unlinked, as prefabs can't contain other prefabs
+pub fn create(world: &mut World, root: Entity, path: &Path) {
+    let instance_path = asset_path(path);
+    let path = &asset_file(&instance_path);
+
+    let scene = build_prefab(world, root, &EntityHashMap::default());
+    if !write_prefab(world, path, &scene) {
+        return;
+    }
+
+    for dynamic_entity in scene.entities.iter() {
+        let mut entity_mut = world.entity_mut(dynamic_entity.entity);
+        entity_mut.remove::<PrefabInstance>();
+        entity_mut.insert(PrefabPart(dynamic_entity.entity.to_bits()));
+    }
+
+    world.entity_mut(root).insert(PrefabInstance {
+        path: instance_path,
+        overrides: Vec::new(),
+    });
+
+    let mut prefabs = world.resource_mut::<Prefabs>();
+    prefabs.failed.remove(path);
+    prefabs.loaded.insert(
+        path.to_path_buf(),
+        LoadedPrefab {
+            scene,
+            modified: modified(path),
+        },
+    );
+}
+
+/// Spawns an instance of the prefab at `path`, returning its root
+pub fn instantiate(world: &mut World, path: &Path) -> Option<Entity> {
+    let instance_path = asset_path(path);
+    let path = &asset_file(&instance_path);
+
+    world.resource_mut::<Prefabs>().failed.remove(path);
+    if !load(world, path) {
+        return None;
+    }
+
+    world.resource_scope::<Prefabs, _>(|world, prefabs| {
+        let scene = &prefabs.loaded.get(path)?.scene;
+
+        let mut entity_map = EntityHashMap::default();
+        if let Err(e) = scene.write_to_world(world, &mut entity_map) {
+            error!("Failed to spawn prefab: {path:?}\n{e:?}");
+            return None;
+        }
+
+        for (id, &entity) in entity_map.iter() {
+            world.entity_mut(entity).insert(PrefabPart(id.to_bits()));
+        }
+
+        let root = entity_map
+            .values()
+            .find(|&&entity| world.get::<Parent>(entity).is_none())
+            .copied()?;
+        world.entity_mut(root).insert(PrefabInstance {
+            path: instance_path,
+            overrides: Vec::new(),
+        });
+
+        Some(root)
+    })
+}
+
+/// Saves an instance back to its prefab file and updates the other instances
+pub fn apply(world: &mut World, root: Entity) {
+    let Some(instance) = world.get::<PrefabInstance>(root) else {
+        info!("The entity isn't a prefab instance.");
+        return;
+    };
+    let path = instance.file();
+    load(world, &path);
+
+    let entities = descendants(world, root);
+
+    let mut ids = EntityHashMap::default();
+    for &entity in entities.iter() {
+        if let Some(part) = world.get::<PrefabPart>(entity) {
+            ids.insert(entity, Entity::from_bits(part.0));
+        }
+    }
+
+    // Entities added to the instance get ids that aren't used by the prefab
+    let mut next_index = world
+        .resource::<Prefabs>()
+        .loaded
+        .get(&path)
+        .into_iter()
+        .flat_map(|prefab| prefab.scene.entities.iter().map(|e| e.entity))
+        .chain(ids.values().copied())
+        .map(|id| id.index() + 1)
+        .max()
+        .unwrap_or(0);
+    for &entity in entities.iter() {
+        if !ids.contains_key(&entity) {
+            ids.insert(entity, Entity::from_raw(next_index));
+            next_index += 1;
+        }
+    }
+
+    let scene = build_prefab(world, root, &ids);
+    if !write_prefab(world, &path, &scene) {
+        return;
+    }
+
+    for (&entity, id) in ids.iter() {
+        world.entity_mut(entity).insert(PrefabPart(id.to_bits()));
+    }
+    if let Some(mut instance) = world.get_mut::<PrefabInstance>(root) {
+        instance.overrides.clear();
+    }
+
+    let old = world.resource_mut::<Prefabs>().loaded.remove(&path);
+    if let Some(old) = old {
+        propagate(world, &path, &old.scene, &scene, Some(root));
+    }
+
+    world.resource_mut::<Prefabs>().loaded.insert(
+        path.clone(),
+        LoadedPrefab {
+            scene,
+            modified: modified(&path),
+        },
+    );
+}
+
+/// Clears the overrides of an instance and resets it to its prefab
+pub fn revert(world: &mut World, root: Entity) {
+    let Some(instance) = world.get::<PrefabInstance>(root) else {
+        info!("The entity isn't a prefab instance.");
+        return;
+    };
+    let path = instance.file();
+    if !load(world, &path) {
+        return;
+    }
+
+    world.resource_scope::<Prefabs, _>(|world, prefabs| {
+        if let Some(prefab) = prefabs.loaded.get(&path) {
+            sync_instance(world, root, None, &prefab.scene);
+        }
+    });
+}
+
+/// Updates every instance of the prefab at `path` from `old` to `new`, except `skip`
+fn propagate(
+    world: &mut World,
+    path: &Path,
+    old: &DynamicScene,
+    new: &DynamicScene,
+    skip: Option<Entity>,
+) {
+    let roots = world
+        .query::<(Entity, &PrefabInstance)>()
+        .iter(world)
+        .filter(|(entity, instance)| instance.file() == path && Some(*entity) != skip)
+        .map(|(entity, _)| entity)
+        .collect::<Vec<Entity>>();
+
+    for root in roots {
+        sync_instance(world, root, Some(old), new);
+    }
+}
+
+/// Updates an instance from the `old` version of its prefab to the `new` one.
+/// Components that differ from `old` become overrides and keep their value.
+/// Without `old` every component is reset and the overrides are cleared
+fn sync_instance(world: &mut World, root: Entity, old: Option<&DynamicScene>, new: &DynamicScene) {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    // Entities of the instance by their id in the prefab
+    let mut parts = EntityHashMap::default();
+    for entity in descendants(world, root) {
+        if let Some(part) = world.get::<PrefabPart>(entity) {
+            parts.insert(Entity::from_bits(part.0), entity);
+        }
+    }
+    let root_id = world
+        .get::<PrefabPart>(root)
+        .map(|part| Entity::from_bits(part.0));
+
+    let mut overrides = match old {
+        Some(_) => world
+            .get::<PrefabInstance>(root)
+            .map(|instance| instance.overrides.clone())
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let mut scene = DynamicScene::default();
+    let mut removed = Vec::new();
+    for new_entity in new.entities.iter() {
+        let id = new_entity.entity;
+        let old_entity = old.and_then(|old| old.entities.iter().find(|e| e.entity == id));
+        let find_old = |type_id: TypeId| {
+            old_entity?.components.iter().find(|component| {
+                component
+                    .get_represented_type_info()
+                    .is_some_and(|info| info.type_id() == type_id)
+            })
+        };
+
+        let mut components = Vec::new();
+        for component in new_entity.components.iter() {
+            let Some(info) = component.get_represented_type_info() else {
+                continue;
+            };
+            // The hierarchy of new entities is linked below
+            if info.type_id() == TypeId::of::<Parent>()
+                || info.type_id() == TypeId::of::<Children>()
+            {
+                continue;
+            }
+
+            if let Some(&existing) = parts.get(&id) {
+                if Some(id) == root_id && info.type_id() == TypeId::of::<Transform>() {
+                    continue;
+                }
+
+                let prefab_override = PrefabOverride {
+                    part: id.to_bits(),
+                    component: info.type_path().to_string(),
+                };
+                if overrides.contains(&prefab_override) {
+                    continue;
+                }
+
+                let current = registry
+                    .get_type_data::<ReflectComponent>(info.type_id())
+                    .and_then(|reflect_component| {
+                        reflect_component.reflect(world.entity(existing))
+                    });
+                if let (Some(old_value), Some(current)) = (find_old(info.type_id()), current) {
+                    if current.reflect_partial_eq(&**old_value) == Some(false) {
+                        overrides.push(prefab_override);
+                        continue;
+                    }
+                }
+            }
+
+            components.push(component.clone_value());
+        }
+
+        // Components removed from the prefab are removed from instances that didn't change them
+        if let (Some(old_entity), Some(&existing)) = (old_entity, parts.get(&id)) {
+            for component in old_entity.components.iter() {
+                let Some(info) = component.get_represented_type_info() else {
+                    continue;
+                };
+                let in_new = new_entity.components.iter().any(|new_component| {
+                    new_component
+                        .get_represented_type_info()
+                        .is_some_and(|new_info| new_info.type_id() == info.type_id())
+                });
+                let prefab_override = PrefabOverride {
+                    part: id.to_bits(),
+                    component: info.type_path().to_string(),
+                };
+
+                if !in_new && !overrides.contains(&prefab_override) {
+                    if let Some(reflect_component) =
+                        registry.get_type_data::<ReflectComponent>(info.type_id())
+                    {
+                        removed.push((existing, reflect_component.clone()));
+                    }
+                }
+            }
+        }
+
+        scene.entities.push(DynamicEntity {
+            entity: id,
+            components,
+        });
+    }
+
+    let mut entity_map = parts.clone();
+    if let Err(e) = scene.write_to_world(world, &mut entity_map) {
+        error!("Failed to update prefab instance {root}.\n{e:?}");
+        return;
+    }
+
+    for (entity, reflect_component) in removed {
+        reflect_component.remove(&mut world.entity_mut(entity));
+    }
+
+    // Link entities added to the prefab
+    for new_entity in new.entities.iter() {
+        if parts.contains_key(&new_entity.entity) {
+            continue;
+        }
+        let Some(&entity) = entity_map.get(&new_entity.entity) else {
+            continue;
+        };
+
+        world
+            .entity_mut(entity)
+            .insert(PrefabPart(new_entity.entity.to_bits()));
+
+        let parent = new_entity
+            .components
+            .iter()
+            .find(|component| is_type::<Parent>(&***component))
+            .and_then(|component| Parent::from_reflect(&**component))
+            .and_then(|parent| entity_map.get(&parent.get()).copied());
+        if let Some(parent) = parent {
+            world.entity_mut(parent).add_child(entity);
+        }
+    }
+
+    // Despawn entities removed from the prefab
+    let new_ids = new
+        .entities
+        .iter()
+        .map(|e| e.entity)
+        .collect::<HashSet<Entity>>();
+    for (id, &entity) in parts.iter() {
+        if new_ids.contains(id) {
+            continue;
+        }
+
+        if let Some(entity_mut) = world.get_entity_mut(entity) {
+            entity_mut.despawn_recursive();
+        }
+    }
+
+    if let Some(mut instance) = world.get_mut::<PrefabInstance>(root) {
+        instance.overrides = overrides;
+    }
+}
+
+/// Reloads prefab files that changed on disk and updates their instances
+fn watch_prefabs(world: &mut World) {
+    // Instances from opened scenes
+    let paths = world
+        .query::<&PrefabInstance>()
+        .iter(world)
+        .map(PrefabInstance::file)
+        .collect::<HashSet<PathBuf>>();
+    for path in paths {
+        load(world, &path);
+    }
+
+    let changed = world
+        .resource::<Prefabs>()
+        .loaded
+        .iter()
+        .filter(|(path, prefab)| modified(path) != prefab.modified)
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<PathBuf>>();
+
+    for path in changed {
+        let Some(new) = read_prefab(world, &path) else {
+            // Wait for the file to change again
+            if let Some(prefab) = world.resource_mut::<Prefabs>().loaded.get_mut(&path) {
+                prefab.modified = modified(&path);
+            }
+            continue;
+        };
+
+        let Some(old) = world.resource_mut::<Prefabs>().loaded.remove(&path) else {
+            continue;
+        };
+
+        info!("Updating instances of {path:?}");
+        propagate(world, &path, &old.scene, &new.scene, None);
+        world.resource_mut::<Prefabs>().loaded.insert(path, new);
+    }
+}
+
+pub struct PrefabPlugin;
+impl Plugin for PrefabPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PrefabInstance>();
+        app.register_type::<PrefabPart>();
+        app.insert_resource(Prefabs::default());
+        app.add_systems(
+            Update,
+            watch_prefabs.run_if(on_timer(Duration::from_secs(1))),
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use bevy::{
+    prelude::*,
+    render::{primitives::Aabb, view::VisibilitySystems},
+};
+
+/// A shape whose mesh is generated from its parameters, so it stays editable after placement.
+/// Only the parameters need to be saved, the mesh is regenerated when they change
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub enum Primitive {
+    Cuboid {
+        size: Vec3,
+    },
+    Sphere {
+        radius: f32,
+        sectors: u32,
+        stacks: u32,
+    },
+    Plane {
+        size: Vec2,
+        subdivisions: u32,
+    },
+    Cylinder {
+        radius: f32,
+        height: f32,
+        resolution: u32,
+        segments: u32,
+    },
+    Cone {
+        radius: f32,
+        height: f32,
+        resolution: u32,
+    },
+    Torus {
+        minor_radius: f32,
+        major_radius: f32,
+        minor_resolution: u32,
+        major_resolution: u32,
+    },
+    Capsule {
+        radius: f32,
+        length: f32,
+        longitudes: u32,
+        latitudes: u32,
+    },
+    Icosphere {
+        radius: f32,
+        subdivisions: u32,
+    },
+    Tetrahedron {
+        size: f32,
+    },
+    /// A rectangle facing +Z
+    Quad {
+        size: Vec2,
+    },
+    /// A flat ring facing +Z
+    Annulus {
+        inner_radius: f32,
+        outer_radius: f32,
+        resolution: u32,
+    },
+    /// A regular polygon extruded along Z
+    Extrusion {
+        radius: f32,
+        sides: u32,
+        depth: f32,
+        segments: u32,
+    },
+}
+
+impl Default for Primitive {
+    fn default() -> Self {
+        Self::CUBOID
+    }
+}
+
+impl Primitive {
+    pub const CUBOID: Self = Self::Cuboid { size: Vec3::ONE };
+    pub const SPHERE: Self = Self::Sphere {
+        radius: 0.5,
+        sectors: 32,
+        stacks: 18,
+    };
+    pub const PLANE: Self = Self::Plane {
+        size: Vec2::ONE,
+        subdivisions: 0,
+    };
+    pub const CYLINDER: Self = Self::Cylinder {
+        radius: 0.5,
+        height: 1.0,
+        resolution: 32,
+        segments: 1,
+    };
+    pub const CONE: Self = Self::Cone {
+        radius: 0.5,
+        height: 1.0,
+        resolution: 32,
+    };
+    pub const TORUS: Self = Self::Torus {
+        minor_radius: 0.25,
+        major_radius: 0.75,
+        minor_resolution: 24,
+        major_resolution: 32,
+    };
+    pub const CAPSULE: Self = Self::Capsule {
+        radius: 0.5,
+        length: 1.0,
+        longitudes: 32,
+        latitudes: 16,
+    };
+    pub const ICOSPHERE: Self = Self::Icosphere {
+        radius: 0.5,
+        subdivisions: 5,
+    };
+    pub const TETRAHEDRON: Self = Self::Tetrahedron { size: 1.0 };
+    pub const QUAD: Self = Self::Quad { size: Vec2::ONE };
+    pub const ANNULUS: Self = Self::Annulus {
+        inner_radius: 0.25,
+        outer_radius: 0.5,
+        resolution: 32,
+    };
+    pub const EXTRUSION: Self = Self::Extrusion {
+        radius: 0.5,
+        sides: 6,
+        depth: 1.0,
+        segments: 1,
+    };
+
+    /// The default of every shape
+    pub const ALL: [Self; 12] = [
+        Self::CUBOID,
+        Self::SPHERE,
+        Self::ICOSPHERE,
+        Self::PLANE,
+        Self::QUAD,
+        Self::CYLINDER,
+        Self::CONE,
+        Self::TORUS,
+        Self::CAPSULE,
+        Self::TETRAHEDRON,
+        Self::ANNULUS,
+        Self::EXTRUSION,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cuboid { .. } => "Cube",
+            Self::Sphere { .. } => "Sphere",
+            Self::Plane { .. } => "Plane",
+            Self::Cylinder { .. } => "Cylinder",
+            Self::Cone { .. } => "Cone",
+            Self::Torus { .. } => "Torus",
+            Self::Capsule { .. } => "Capsule",
+            Self::Icosphere { .. } => "Icosphere",
+            Self::Tetrahedron { .. } => "Tetrahedron",
+            Self::Quad { .. } => "Quad",
+            Self::Annulus { .. } => "Annulus",
+            Self::Extrusion { .. } => "Extrusion",
+        }
+    }
+
+    /// Whether `other` is the same shape, ignoring the parameters
+    pub fn is_shape(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        // Resolutions are clamped, as the builders panic or produce nothing below them
+        match *self {
+            Self::Cuboid { size } => Cuboid::from_size(size).into(),
+            Self::Sphere {
+                radius,
+                sectors,
+                stacks,
+            } => Sphere::new(radius)
+                .mesh()
+                .uv(sectors.max(3) as usize, stacks.max(2) as usize),
+            Self::Plane { size, subdivisions } => Plane3d::default()
+                .mesh()
+                .size(size.x, size.y)
+                .subdivisions(subdivisions)
+                .into(),
+            Self::Cylinder {
+                radius,
+                height,
+                resolution,
+                segments,
+            } => Cylinder::new(radius, height)
+                .mesh()
+                .resolution(resolution.max(3))
+                .segments(segments.max(1))
+                .into(),
+            Self::Cone {
+                radius,
+                height,
+                resolution,
+            } => Cone { radius, height }
+                .mesh()
+                .resolution(resolution.max(3))
+                .into(),
+            Self::Torus {
+                minor_radius,
+                major_radius,
+                minor_resolution,
+                major_resolution,
+            } => Torus {
+                minor_radius,
+                major_radius,
+            }
+            .mesh()
+            .minor_resolution(minor_resolution.max(3) as usize)
+            .major_resolution(major_resolution.max(3) as usize)
+            .into(),
+            Self::Capsule {
+                radius,
+                length,
+                longitudes,
+                latitudes,
+            } => Capsule3d::new(radius, length)
+                .mesh()
+                .longitudes(longitudes.max(3) as usize)
+                .latitudes(latitudes.max(2) as usize)
+                .into(),
+            Self::Icosphere {
+                radius,
+                subdivisions,
+            } => {
+                // 80 or more subdivisions fail
+                let sphere = Sphere::new(radius);
+                sphere
+                    .mesh()
+                    .ico(subdivisions.min(79) as usize)
+                    .unwrap_or_else(|_| sphere.mesh().uv(32, 18))
+            }
+            Self::Tetrahedron { size } => Tetrahedron {
+                vertices: Tetrahedron::default().vertices.map(|vertex| vertex * size),
+            }
+            .into(),
+            Self::Quad { size } => Rectangle::from_size(size).into(),
+            Self::Annulus {
+                inner_radius,
+                outer_radius,
+                resolution,
+            } => Annulus::new(inner_radius, outer_radius)
+                .mesh()
+                .resolution(resolution.max(3) as usize)
+                .into(),
+            Self::Extrusion {
+                radius,
+                sides,
+                depth,
+                segments,
+            } => Extrusion::new(RegularPolygon::new(radius, sides.max(3) as usize), depth)
+                .mesh()
+                .segments(segments.max(1) as usize)
+                .into(),
+        }
+    }
+}
+
+/// Regenerates the meshes of changed primitives, including primitives loaded from scenes
+fn update_meshes(
+    query: Query<(Entity, &Primitive), Changed<Primitive>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut commands: Commands,
+) {
+    for (entity, primitive) in query.iter() {
+        // Normal and parallax maps need tangents
+        let mut mesh = primitive.mesh();
+        if let Err(e) = mesh.generate_tangents() {
+            warn!("Failed to generate tangents for {}: {e}", primitive.name());
+        }
+
+        // A new mesh, as duplicates of the entity share the old one.
+        // The `Aabb` is removed to be computed again for the new mesh
+        commands
+            .entity(entity)
+            .insert(meshes.add(mesh))
+            .remove::<Aabb>();
+    }
+}
+
+pub struct PrimitivePlugin;
+impl Plugin for PrimitivePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Primitive>();
+        app.add_systems(
+            PostUpdate,
+            update_meshes.before(VisibilitySystems::CalculateBounds),
+        );
+    }
+}
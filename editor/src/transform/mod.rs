@@ -1,6 +1,6 @@
 mod gizmo;
 mod input;
-mod observers;
+pub mod observers;
 
 use bevy::{prelude::*, utils::HashMap};
 
@@ -1,6 +1,6 @@
 use bevy::{prelude::*, utils::HashMap};
 
-use crate::{camera::Flycam, EditorAction, EditorEntity, SelectedEntities};
+use crate::{camera::ActiveCamera, EditorAction, EditorEntity, Locked, SelectedEntities};
 
 use super::{TransformEntities, TransformHome, TransformMode};
 
@@ -27,7 +27,9 @@ pub fn transform_selected(
     trigger: Trigger<TransformSelected>,
     selected: Res<SelectedEntities>,
     transform_query: Query<&Transform>,
-    camera_query: Query<&Transform, With<Flycam>>,
+    locked_query: Query<(), With<Locked>>,
+    parent_query: Query<&Parent>,
+    camera_query: Query<&Transform, With<ActiveCamera>>,
     transform_entities: Option<Res<TransformEntities>>,
     mut editor_action: ResMut<EditorAction>,
     mut commands: Commands,
@@ -74,6 +76,14 @@ pub fn transform_selected(
     };
 
     for entity in selected.0.iter() {
+        // Locked entities and their descendants stay in place
+        if std::iter::once(*entity)
+            .chain(parent_query.iter_ancestors(*entity))
+            .any(|entity| locked_query.contains(entity))
+        {
+            continue;
+        }
+
         let Ok(transform) = transform_query.get(*entity) else {
             info!("The transform operation has been cancelled.");
             warn!("A selected entity doesn't have a `Transform` component.");
@@ -90,7 +100,12 @@ pub fn transform_selected(
         resource.center += transform.translation;
     }
 
-    resource.center /= selected.0.len() as f32;
+    if resource.entities.is_empty() {
+        info!("All selected entities are locked.");
+        return;
+    }
+
+    resource.center /= resource.entities.len() as f32;
     commands.insert_resource(resource);
     editor_action.0 = Some(crate::TRANSFORM_ACTION_ID);
 }
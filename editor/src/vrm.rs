@@ -0,0 +1,284 @@
+use std::{f32::consts::PI, path::PathBuf};
+
+use bevy::{
+    asset::LoadState,
+    gltf::{Gltf, GltfLoaderSettings},
+    prelude::*,
+    scene::SceneInstance,
+    utils::HashMap,
+};
+use gltf::json::Value;
+
+/// The root of a VRM avatar, with the metadata of its file
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Vrm {
+    /// The `.vrm` file
+    pub path: String,
+    /// `0.x` or `1.0`, empty for files without VRM extensions
+    pub spec_version: String,
+    pub name: String,
+    pub version: String,
+    pub authors: String,
+    pub contact: String,
+    pub references: String,
+    pub license: String,
+    pub avatar_permission: String,
+    pub commercial_usage: String,
+    pub violent_usage: String,
+    pub sexual_usage: String,
+}
+
+/// The humanoid bone of a joint in a [`Vrm`] skeleton, like `hips` or `leftUpperArm`
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct HumanoidBone(pub String);
+
+/// A [`Vrm`] whose file is loading
+#[derive(Component)]
+pub struct LoadingVrm(pub Handle<Gltf>);
+
+/// A [`Vrm`] whose scene is spawning, with its humanoid bones by node name
+#[derive(Component)]
+struct SpawningVrm(HashMap<String, String>);
+
+/// Loads a `.vrm` file, which is a binary glTF with VRM extensions
+pub fn load(asset_server: &AssetServer, path: PathBuf) -> Handle<Gltf> {
+    // The extensions are only kept in the source document
+    asset_server.load_with_settings(path, |settings: &mut GltfLoaderSettings| {
+        settings.include_source = true;
+    })
+}
+
+/// The VRM extension of the file, VRM 1.0 is `VRMC_vrm` and VRM 0.x is `VRM`
+fn extension(source: &gltf::Gltf) -> Option<(&Value, bool)> {
+    source
+        .extension_value("VRMC_vrm")
+        .map(|extension| (extension, false))
+        .or_else(|| {
+            source
+                .extension_value("VRM")
+                .map(|extension| (extension, true))
+        })
+}
+
+/// A metadata field as text, lists are joined and missing fields are empty
+fn text(meta: &Value, key: &str) -> String {
+    match meta.get(key) {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(", "),
+        // VRM 1.0 permissions, named like their VRM 0.x counterparts
+        Some(Value::Bool(true)) => "Allow".to_string(),
+        Some(Value::Bool(false)) => "Disallow".to_string(),
+        Some(Value::Null) | None => String::new(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn read_meta(vrm: &mut Vrm, extension: &Value, vrm0: bool) {
+    let Some(meta) = extension.get("meta") else {
+        return;
+    };
+
+    if vrm0 {
+        vrm.spec_version = "0.x".to_string();
+        vrm.name = text(meta, "title");
+        vrm.authors = text(meta, "author");
+        vrm.references = text(meta, "reference");
+        vrm.license = match text(meta, "licenseName").as_str() {
+            "Other" => text(meta, "otherLicenseUrl"),
+            license => license.to_string(),
+        };
+        vrm.avatar_permission = text(meta, "allowedUserName");
+        // Misspelled in the specification
+        vrm.commercial_usage = text(meta, "commercialUssageName");
+        vrm.violent_usage = text(meta, "violentUssageName");
+        vrm.sexual_usage = text(meta, "sexualUssageName");
+    } else {
+        vrm.spec_version = "1.0".to_string();
+        vrm.name = text(meta, "name");
+        vrm.authors = text(meta, "authors");
+        vrm.references = text(meta, "references");
+        vrm.license = text(meta, "licenseUrl");
+        vrm.avatar_permission = text(meta, "avatarPermission");
+        vrm.commercial_usage = text(meta, "commercialUsage");
+        vrm.violent_usage = text(meta, "allowExcessivelyViolentUsage");
+        vrm.sexual_usage = text(meta, "allowExcessivelySexualUsage");
+    }
+
+    vrm.version = text(meta, "version");
+    vrm.contact = text(meta, "contactInformation");
+}
+
+/// The humanoid bones by the name the glTF loader gives their node
+fn human_bones(source: &gltf::Gltf, extension: &Value, vrm0: bool) -> HashMap<String, String> {
+    let Some(human_bones) = extension.get("humanoid").and_then(|h| h.get("humanBones")) else {
+        return HashMap::new();
+    };
+
+    // VRM 0.x lists `{ bone, node }`, VRM 1.0 maps bones to `{ node }`
+    let nodes = if vrm0 {
+        human_bones
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|bone| Some((bone.get("bone")?.as_str()?, bone.get("node")?.as_u64()?)))
+            .collect::<Vec<_>>()
+    } else {
+        human_bones
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(bone, value)| Some((bone.as_str(), value.get("node")?.as_u64()?)))
+            .collect::<Vec<_>>()
+    };
+
+    nodes
+        .into_iter()
+        .filter_map(|(bone, index)| {
+            let node = source.nodes().nth(index as usize)?;
+            let name = node
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("GltfNode{index}"));
+            Some((name, bone.to_string()))
+        })
+        .collect()
+}
+
+/// The indices of the materials using the MToon shader
+fn mtoon_materials(source: &gltf::Gltf, extension: &Value, vrm0: bool) -> Vec<usize> {
+    if vrm0 {
+        // Material properties are in the order of the glTF materials
+        extension
+            .get("materialProperties")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, properties)| {
+                properties.get("shader").and_then(Value::as_str) == Some("VRM/MToon")
+            })
+            .map(|(index, _)| index)
+            .collect()
+    } else {
+        source
+            .materials()
+            .filter(|material| material.extension_value("VRMC_materials_mtoon").is_some())
+            .filter_map(|material| material.index())
+            .collect()
+    }
+}
+
+/// Approximates MToon, a toon shader lit by the scene lights.
+/// Files ship an unlit fallback, which ignores the lights of the environment
+fn approximate_mtoon(material: &mut StandardMaterial) {
+    material.unlit = false;
+    material.metallic = 0.0;
+    material.perceptual_roughness = 1.0;
+    material.reflectance = 0.0;
+}
+
+/// Spawns the scenes of loaded VRM files and reads their extensions
+fn spawn_vrms(
+    mut query: Query<(Entity, &LoadingVrm, &mut Vrm, &mut Transform)>,
+    gltfs: Res<Assets<Gltf>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (entity, loading, mut vrm, mut transform) in query.iter_mut() {
+        if let LoadState::Failed(e) = asset_server.load_state(&loading.0) {
+            error!("Failed to load VRM: {:?}\n{e:?}", vrm.path);
+            commands.entity(entity).remove::<LoadingVrm>();
+            continue;
+        }
+
+        let Some(gltf) = gltfs.get(&loading.0) else {
+            continue;
+        };
+        commands.entity(entity).remove::<LoadingVrm>();
+
+        let Some(scene) = gltf
+            .default_scene
+            .clone()
+            .or_else(|| gltf.scenes.first().cloned())
+        else {
+            error!("The VRM file has no scene: {:?}", vrm.path);
+            continue;
+        };
+
+        let mut bones = HashMap::new();
+        match gltf
+            .source
+            .as_ref()
+            .and_then(|source| Some((source, extension(source)?)))
+        {
+            Some((source, (extension, vrm0))) => {
+                read_meta(&mut vrm, extension, vrm0);
+                bones = human_bones(source, extension, vrm0);
+
+                for index in mtoon_materials(source, extension, vrm0) {
+                    if let Some(material) = gltf
+                        .materials
+                        .get(index)
+                        .and_then(|handle| materials.get_mut(handle))
+                    {
+                        approximate_mtoon(material);
+                    }
+                }
+
+                // VRM 0.x avatars face -Z, VRM 1.0 avatars face +Z like glTF
+                if vrm0 {
+                    transform.rotate_y(PI);
+                }
+            }
+            None => warn!("The file has no VRM extension: {:?}", vrm.path),
+        }
+
+        commands.entity(entity).insert((scene, SpawningVrm(bones)));
+    }
+}
+
+/// Marks the humanoid bones once the scene of a [`Vrm`] is spawned
+fn tag_humanoid_bones(
+    query: Query<(Entity, &SceneInstance, &SpawningVrm)>,
+    name_query: Query<&Name>,
+    scene_spawner: Res<SceneSpawner>,
+    mut commands: Commands,
+) {
+    for (entity, instance, spawning) in query.iter() {
+        if !scene_spawner.instance_is_ready(**instance) {
+            continue;
+        }
+
+        for scene_entity in scene_spawner.iter_instance_entities(**instance) {
+            let Some(bone) = name_query
+                .get(scene_entity)
+                .ok()
+                .and_then(|name| spawning.0.get(name.as_str()))
+            else {
+                continue;
+            };
+
+            commands
+                .entity(scene_entity)
+                .insert(HumanoidBone(bone.clone()));
+        }
+
+        commands.entity(entity).remove::<SpawningVrm>();
+    }
+}
+
+pub struct VrmPlugin;
+impl Plugin for VrmPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Vrm>();
+        app.register_type::<HumanoidBone>();
+        app.add_systems(Update, (spawn_vrms, tag_humanoid_bones).chain());
+    }
+}